@@ -0,0 +1,209 @@
+//! The base58 encodings of the ledger: classic addresses like `rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh`
+//! and X-addresses, which also carry the destination tag. See [1] and [2]
+//!
+//! 1: https://xrpl.org/base58-encodings.html
+//! 2: https://github.com/XRPLF/XRPL-Standards/tree/master/XLS-0005-tagged-addresses
+
+use crate::Account;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// The ledger's own base58 alphabet, which is not Bitcoin's.
+const ALPHABET: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+/// The type prefix of a classic address.
+const ACCOUNT_ID_PREFIX: u8 = 0x00;
+const X_ADDRESS_MAIN_NET_PREFIX: [u8; 2] = [0x05, 0x44];
+const X_ADDRESS_TEST_NET_PREFIX: [u8; 2] = [0x04, 0x93];
+
+/// The first four bytes of the double sha256 of the payload.
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(&Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// The payload followed by its checksum, in base58.
+pub(crate) fn encode_check(payload: &[u8]) -> String {
+    let mut bytes = payload.to_vec();
+    bytes.extend_from_slice(&checksum(payload));
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    // Little endian base58 digits
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut encoded = char::from(ALPHABET[0]).to_string().repeat(zeros);
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|digit| char::from(ALPHABET[*digit as usize])),
+    );
+    encoded
+}
+
+/// The payload of a base58 string, once its checksum is checked.
+pub(crate) fn decode_check(s: &str) -> Result<Vec<u8>, String> {
+    let zeros = s.bytes().take_while(|c| *c == ALPHABET[0]).count();
+    // Little endian bytes
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    for c in s.bytes().skip(zeros) {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or_else(|| format!("{:?} is not a base58 character", char::from(c)))?
+            as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.resize(bytes.len() + zeros, 0);
+    bytes.reverse();
+    if bytes.len() < 4 {
+        return Err(format!("{:?} is too short to hold a checksum", s));
+    }
+    let (payload, check) = bytes.split_at(bytes.len() - 4);
+    if checksum(payload) != check {
+        return Err(format!("{:?} has a bad checksum", s));
+    }
+    Ok(payload.to_vec())
+}
+
+/// The 20 bytes of the account ID of a classic address.
+pub(crate) fn decode_account_id(s: &str) -> Result<[u8; 20], String> {
+    let payload = decode_check(s)?;
+    match payload.split_first() {
+        Some((&ACCOUNT_ID_PREFIX, account_id)) if account_id.len() == 20 => {
+            let mut id = [0; 20];
+            id.copy_from_slice(account_id);
+            Ok(id)
+        }
+        _ => Err(format!("{:?} is not an account address", s)),
+    }
+}
+
+/// The classic address of an account ID.
+pub(crate) fn encode_account_id(account_id: &[u8; 20]) -> String {
+    let mut payload = vec![ACCOUNT_ID_PREFIX];
+    payload.extend_from_slice(account_id);
+    encode_check(&payload)
+}
+
+/// An address that carries the destination tag along with the account, like
+/// `XVLhHMPHU98es4dbozjVtdWzVrDjtV8AqEL4xcZj5whKbmc`, so it cannot be forgotten. It also
+/// says whether it is meant for the main network or a test network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XAddress {
+    ///The classic address of the account
+    pub account: Account,
+    ///
+    pub tag: Option<u32>,
+    ///True when the address is for a test network, they start with T instead of X
+    pub test_net: bool,
+}
+
+impl XAddress {
+    /// The X-address of `account`, with an optional tag.
+    pub fn new(account: Account, tag: Option<u32>, test_net: bool) -> Self {
+        XAddress {
+            account,
+            tag,
+            test_net,
+        }
+    }
+}
+
+impl fmt::Display for XAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut payload = Vec::with_capacity(31);
+        payload.extend_from_slice(if self.test_net {
+            &X_ADDRESS_TEST_NET_PREFIX
+        } else {
+            &X_ADDRESS_MAIN_NET_PREFIX
+        });
+        payload.extend_from_slice(&self.account.account_id());
+        payload.push(self.tag.is_some() as u8);
+        payload.extend_from_slice(&u64::from(self.tag.unwrap_or(0)).to_le_bytes());
+        f.write_str(&encode_check(&payload))
+    }
+}
+
+impl FromStr for XAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let payload = decode_check(s)?;
+        if payload.len() != 31 {
+            return Err(format!("{:?} is not an X-address", s));
+        }
+        let test_net = match [payload[0], payload[1]] {
+            X_ADDRESS_MAIN_NET_PREFIX => false,
+            X_ADDRESS_TEST_NET_PREFIX => true,
+            _ => return Err(format!("{:?} is not an X-address", s)),
+        };
+        let mut account_id = [0; 20];
+        account_id.copy_from_slice(&payload[2..22]);
+        let mut tag = [0; 8];
+        tag.copy_from_slice(&payload[23..31]);
+        let tag = u64::from_le_bytes(tag);
+        let tag = match payload[22] {
+            0 if tag == 0 => None,
+            1 if tag <= u64::from(u32::MAX) => Some(tag as u32),
+            _ => return Err(format!("{:?} has an unsupported tag", s)),
+        };
+        Ok(XAddress {
+            account: Account::from_account_id(&account_id),
+            tag,
+            test_net,
+        })
+    }
+}
+
+#[test]
+fn address_test() {
+    let account: Account = "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf".parse().unwrap();
+    assert_eq!(Account::from_account_id(&account.account_id()), account);
+    assert!("rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpF"
+        .parse::<Account>()
+        .is_err());
+    assert!("rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYp0"
+        .parse::<Account>()
+        .is_err());
+
+    for (tag, encoded) in &[
+        (None, "XVLhHMPHU98es4dbozjVtdWzVrDjtV5fdx1mHp98tDMoQXb"),
+        (Some(0), "XVLhHMPHU98es4dbozjVtdWzVrDjtV8AqEL4xcZj5whKbmc"),
+        (Some(1), "XVLhHMPHU98es4dbozjVtdWzVrDjtV8xvjGQTYPiAx6gwDC"),
+    ] {
+        let x_address = XAddress::new(account.clone(), *tag, false);
+        assert_eq!(x_address.to_string(), *encoded);
+        assert_eq!(encoded.parse::<XAddress>().unwrap(), x_address);
+    }
+    for x_address in &[
+        XAddress::new(account.clone(), Some(u32::MAX), true),
+        XAddress::new(account, None, true),
+    ] {
+        let encoded = x_address.to_string();
+        assert_eq!(encoded.starts_with('T'), x_address.test_net);
+        assert_eq!(&encoded.parse::<XAddress>().unwrap(), x_address);
+    }
+    // A classic address is not an X-address
+    assert!("rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf"
+        .parse::<XAddress>()
+        .is_err());
+}
@@ -0,0 +1,562 @@
+use bigdecimal::BigDecimal;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// An amount of XRP, counted in drops, the smallest unit of XRP. See [1]
+///
+/// The ledger writes XRP amounts as strings of drops, which is how this type goes in and out
+/// of json. `{}` displays the drops and `{:#}` the XRP, like `1.5` for 1500000 drops.
+///
+/// 1: https://xrpl.org/currency-formats.html#xrp-amounts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Drops(u64);
+
+impl Drops {
+    /// No XRP at all.
+    pub const ZERO: Drops = Drops(0);
+    /// How many drops make one XRP.
+    pub const DROPS_PER_XRP: u64 = 1_000_000;
+    /// Every XRP there will ever be, 100 billion XRP. No amount can be larger.
+    pub const MAX: Drops = Drops(100_000_000_000 * Self::DROPS_PER_XRP);
+
+    /// An amount of `drops`, `None` if it is more than [`Drops::MAX`].
+    pub fn from_drops(drops: u64) -> Option<Self> {
+        if drops > Self::MAX.0 {
+            None
+        } else {
+            Some(Drops(drops))
+        }
+    }
+
+    /// An amount of whole XRP, `None` if it is more than [`Drops::MAX`].
+    pub fn from_xrp(xrp: u64) -> Option<Self> {
+        xrp.checked_mul(Self::DROPS_PER_XRP)
+            .and_then(Self::from_drops)
+    }
+
+    /// An amount written in XRP, like `1.5`. `None` if it is negative, has more than six
+    /// decimals, or is more than [`Drops::MAX`].
+    pub fn from_xrp_decimal(xrp: &BigDecimal) -> Option<Self> {
+        let drops = xrp * &BigDecimal::from(Self::DROPS_PER_XRP);
+        if !drops.is_integer() {
+            return None;
+        }
+        bigdecimal::ToPrimitive::to_u64(&drops).and_then(Self::from_drops)
+    }
+
+    /// The amount in drops.
+    pub fn drops(self) -> u64 {
+        self.0
+    }
+
+    /// The whole XRP in the amount, the drops short of one more XRP are left out.
+    pub fn whole_xrp(self) -> u64 {
+        self.0 / Self::DROPS_PER_XRP
+    }
+
+    /// The amount in XRP, exactly.
+    pub fn to_xrp(self) -> BigDecimal {
+        BigDecimal::from(self.0) / BigDecimal::from(Self::DROPS_PER_XRP)
+    }
+
+    /// `None` if the sum is more than [`Drops::MAX`].
+    pub fn checked_add(self, other: Drops) -> Option<Drops> {
+        self.0.checked_add(other.0).and_then(Self::from_drops)
+    }
+
+    /// `None` if `other` is larger.
+    pub fn checked_sub(self, other: Drops) -> Option<Drops> {
+        self.0.checked_sub(other.0).map(Drops)
+    }
+
+    /// `None` if the product is more than [`Drops::MAX`].
+    pub fn checked_mul(self, factor: u64) -> Option<Drops> {
+        self.0.checked_mul(factor).and_then(Self::from_drops)
+    }
+
+    /// Rounded down, `None` if `divisor` is zero.
+    pub fn checked_div(self, divisor: u64) -> Option<Drops> {
+        self.0.checked_div(divisor).map(Drops)
+    }
+
+    /// Zero if `other` is larger.
+    pub fn saturating_sub(self, other: Drops) -> Drops {
+        Drops(self.0.saturating_sub(other.0))
+    }
+}
+
+impl fmt::Display for Drops {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !f.alternate() {
+            return write!(f, "{}", self.0);
+        }
+        let fraction = self.0 % Self::DROPS_PER_XRP;
+        if fraction == 0 {
+            write!(f, "{}", self.whole_xrp())
+        } else {
+            let fraction = format!("{:06}", fraction);
+            write!(f, "{}.{}", self.whole_xrp(), fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Drops {
+    type Err = String;
+    /// Parses a number of drops, like the ledger writes them.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let drops: u64 = s
+            .parse()
+            .map_err(|_| format!("{} is not a number of drops", s))?;
+        Self::from_drops(drops).ok_or_else(|| format!("{} drops is more than all the XRP", s))
+    }
+}
+
+impl From<Drops> for u64 {
+    fn from(drops: Drops) -> u64 {
+        drops.0
+    }
+}
+
+impl Serialize for Drops {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Drops {
+    /// Takes the string of drops the ledger uses, and plain numbers, which some responses
+    /// like `server_state` use.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DropsVisitor;
+        impl<'de> Visitor<'de> for DropsVisitor {
+            type Value = Drops;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number of drops of XRP")
+            }
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Drops, E> {
+                s.parse().map_err(E::custom)
+            }
+            fn visit_u64<E: de::Error>(self, drops: u64) -> Result<Drops, E> {
+                Drops::from_drops(drops)
+                    .ok_or_else(|| E::custom(format!("{} drops is more than all the XRP", drops)))
+            }
+        }
+        deserializer.deserialize_any(DropsVisitor)
+    }
+}
+
+/// The value of an issued currency amount, with the precision and the rounding of the
+/// ledger. See [1]
+///
+/// The ledger keeps these values as a mantissa of 16 significant digits, between 10^15 and
+/// 10^16 - 1, and an exponent between -96 and 80. The arithmetic follows rippled digit for
+/// digit: values too small to represent become zero, values too large are an overflow, and
+/// a result is exactly what the ledger would compute. It goes in and out of json as the
+/// string the ledger writes, like `"1.5"` or `"1000000000000000e-96"`.
+///
+/// 1: https://xrpl.org/currency-formats.html#issued-currency-amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IssuedValue {
+    mantissa: u64,
+    exponent: i32,
+    negative: bool,
+}
+
+impl IssuedValue {
+    /// The smallest mantissa of a value that is not zero.
+    pub const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+    /// The largest mantissa.
+    pub const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+    /// The smallest exponent of a value that is not zero.
+    pub const MIN_EXPONENT: i32 = -96;
+    /// The largest exponent.
+    pub const MAX_EXPONENT: i32 = 80;
+    /// Zero, which the ledger keeps with an exponent of -100.
+    pub const ZERO: IssuedValue = IssuedValue {
+        mantissa: 0,
+        exponent: -100,
+        negative: false,
+    };
+
+    /// The value `mantissa * 10^exponent`, normalized the way the ledger does it, which
+    /// drops the digits past the 16th. `None` if it is too large to be represented.
+    pub fn new(mantissa: i64, exponent: i32) -> Option<Self> {
+        Self::canonicalize(u128::from(mantissa.unsigned_abs()), exponent, mantissa < 0)
+    }
+
+    /// The mantissa, without the sign, between [`IssuedValue::MIN_MANTISSA`] and
+    /// [`IssuedValue::MAX_MANTISSA`], or 0.
+    pub fn mantissa(&self) -> u64 {
+        self.mantissa
+    }
+
+    /// The exponent, between [`IssuedValue::MIN_EXPONENT`] and [`IssuedValue::MAX_EXPONENT`],
+    /// or -100 for zero.
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    ///
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    ///
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The value with the opposite sign.
+    pub fn negate(self) -> Self {
+        if self.is_zero() {
+            self
+        } else {
+            IssuedValue {
+                negative: !self.negative,
+                ..self
+            }
+        }
+    }
+
+    /// The value without its sign.
+    pub fn abs(self) -> Self {
+        IssuedValue {
+            negative: false,
+            ..self
+        }
+    }
+
+    /// The value as an arbitrary precision decimal, exactly.
+    pub fn to_big_decimal(&self) -> BigDecimal {
+        self.to_string()
+            .parse()
+            .expect("The canonical string of an issued value is a decimal")
+    }
+
+    /// The sum, with the digits of the smaller value that do not fit dropped first, like the
+    /// ledger does. `None` on overflow.
+    pub fn checked_add(self, other: IssuedValue) -> Option<IssuedValue> {
+        if self.is_zero() {
+            return Some(other);
+        }
+        if other.is_zero() {
+            return Some(self);
+        }
+        let (mut value1, mut exponent1) = (self.signed_mantissa(), self.exponent);
+        let (mut value2, mut exponent2) = (other.signed_mantissa(), other.exponent);
+        while exponent1 < exponent2 {
+            value1 /= 10;
+            exponent1 += 1;
+        }
+        while exponent2 < exponent1 {
+            value2 /= 10;
+            exponent2 += 1;
+        }
+        let sum = value1 + value2;
+        if (-10..=10).contains(&sum) {
+            return Some(Self::ZERO);
+        }
+        Self::new(sum, exponent1)
+    }
+
+    /// `self + (-other)`, see [`IssuedValue::checked_add`].
+    pub fn checked_sub(self, other: IssuedValue) -> Option<IssuedValue> {
+        self.checked_add(other.negate())
+    }
+
+    /// The product, rounded the way the ledger does it. `None` on overflow.
+    pub fn checked_mul(self, other: IssuedValue) -> Option<IssuedValue> {
+        if self.is_zero() || other.is_zero() {
+            return Some(Self::ZERO);
+        }
+        let product =
+            u128::from(self.mantissa) * u128::from(other.mantissa) / 100_000_000_000_000 + 7;
+        Self::canonicalize(
+            product,
+            self.exponent + other.exponent + 14,
+            self.negative != other.negative,
+        )
+    }
+
+    /// The quotient, rounded the way the ledger does it. `None` when dividing by zero and on
+    /// overflow.
+    pub fn checked_div(self, other: IssuedValue) -> Option<IssuedValue> {
+        if other.is_zero() {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(Self::ZERO);
+        }
+        let quotient =
+            u128::from(self.mantissa) * 100_000_000_000_000_000 / u128::from(other.mantissa) + 5;
+        Self::canonicalize(
+            quotient,
+            self.exponent - other.exponent - 17,
+            self.negative != other.negative,
+        )
+    }
+
+    fn signed_mantissa(&self) -> i64 {
+        if self.negative {
+            -(self.mantissa as i64)
+        } else {
+            self.mantissa as i64
+        }
+    }
+
+    /// Brings the mantissa in range, truncating the extra digits, like `STAmount::canonicalize`.
+    fn canonicalize(mut mantissa: u128, mut exponent: i32, negative: bool) -> Option<Self> {
+        if mantissa == 0 {
+            return Some(Self::ZERO);
+        }
+        let (min, max) = (
+            u128::from(Self::MIN_MANTISSA),
+            u128::from(Self::MAX_MANTISSA),
+        );
+        while mantissa < min && exponent > Self::MIN_EXPONENT {
+            mantissa *= 10;
+            exponent -= 1;
+        }
+        while mantissa > max {
+            if exponent >= Self::MAX_EXPONENT {
+                return None;
+            }
+            mantissa /= 10;
+            exponent += 1;
+        }
+        if exponent < Self::MIN_EXPONENT || mantissa < min {
+            return Some(Self::ZERO);
+        }
+        if exponent > Self::MAX_EXPONENT {
+            return None;
+        }
+        Some(IssuedValue {
+            mantissa: mantissa as u64,
+            exponent,
+            negative,
+        })
+    }
+}
+
+impl Default for IssuedValue {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl PartialOrd for IssuedValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IssuedValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |value: &IssuedValue| match (value.is_zero(), value.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        let magnitude = (self.exponent, self.mantissa).cmp(&(other.exponent, other.mantissa));
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if self.negative => magnitude.reverse(),
+            Ordering::Equal => magnitude,
+            different => different,
+        }
+    }
+}
+
+impl fmt::Display for IssuedValue {
+    /// The canonical string of the ledger: plain decimals for exponents between -25 and -5,
+    /// the mantissa and the exponent like `1000000000000000e-96` otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        if self.exponent != 0 && (self.exponent < -25 || self.exponent > -5) {
+            return write!(f, "{}e{}", self.mantissa, self.exponent);
+        }
+        let digits = self.mantissa.to_string();
+        if self.exponent == 0 {
+            return f.write_str(&digits);
+        }
+        let fraction_length = (-self.exponent) as usize;
+        let digits = format!("{:0>width$}", digits, width = fraction_length + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - fraction_length);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            f.write_str(integer)
+        } else {
+            write!(f, "{}.{}", integer, fraction)
+        }
+    }
+}
+
+impl FromStr for IssuedValue {
+    type Err = String;
+    /// Parses a decimal like the ledger accepts them, like `-1.5` or `15e-1`, with at most
+    /// 32 digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} is not a valid issued currency value", s);
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (number, exponent) = match unsigned.find(&['e', 'E'][..]) {
+            Some(e) => {
+                let exponent = &unsigned[e + 1..];
+                let digits = exponent.trim_start_matches(&['+', '-'][..]);
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                (
+                    &unsigned[..e],
+                    exponent.parse::<i32>().map_err(|_| invalid())?,
+                )
+            }
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = match number.find('.') {
+            Some(dot) => (&number[..dot], &number[dot + 1..]),
+            None => (number, ""),
+        };
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty()
+            || (integer.len() > 1 && integer.starts_with('0'))
+            || !all_digits(integer)
+            || (number.contains('.') && fraction.is_empty())
+            || !all_digits(fraction)
+        {
+            return Err(invalid());
+        }
+        if integer.len() + fraction.len() > 32 {
+            return Err(format!("{} has too many digits", s));
+        }
+        let mantissa: u128 = format!("{}{}", integer, fraction)
+            .parse()
+            .map_err(|_| invalid())?;
+        let exponent = exponent
+            .checked_sub(fraction.len() as i32)
+            .ok_or_else(invalid)?;
+        Self::canonicalize(mantissa, exponent, negative)
+            .ok_or_else(|| format!("{} is too large for an issued currency value", s))
+    }
+}
+
+impl Serialize for IssuedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IssuedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IssuedValueVisitor;
+        impl<'de> Visitor<'de> for IssuedValueVisitor {
+            type Value = IssuedValue;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an issued currency value")
+            }
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<IssuedValue, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_str(IssuedValueVisitor)
+    }
+}
+
+#[test]
+fn drops_test() {
+    let drops: Drops = serde_json::from_str("\"1500000\"").unwrap();
+    assert_eq!(drops.drops(), 1_500_000);
+    assert_eq!(serde_json::to_string(&drops).unwrap(), "\"1500000\"");
+    assert_eq!(serde_json::from_str::<Drops>("12").unwrap().drops(), 12);
+    assert!(serde_json::from_str::<Drops>("\"1.5\"").is_err());
+    assert!(serde_json::from_str::<Drops>("\"-1\"").is_err());
+    assert!(serde_json::from_str::<Drops>("\"100000000000000001\"").is_err());
+
+    assert_eq!(format!("{}", drops), "1500000");
+    assert_eq!(format!("{:#}", drops), "1.5");
+    assert_eq!(format!("{:#}", Drops::from_xrp(20).unwrap()), "20");
+    assert_eq!(format!("{:#}", Drops::from_drops(12).unwrap()), "0.000012");
+    assert_eq!(drops.whole_xrp(), 1);
+    assert_eq!(drops.to_xrp(), "1.5".parse::<BigDecimal>().unwrap());
+    assert_eq!(
+        Drops::from_xrp_decimal(&"0.00001".parse().unwrap()),
+        Drops::from_drops(10)
+    );
+    assert_eq!(Drops::from_xrp_decimal(&"0.0000001".parse().unwrap()), None);
+
+    assert_eq!(Drops::from_xrp(100_000_000_000), Some(Drops::MAX));
+    assert_eq!(Drops::from_xrp(100_000_000_001), None);
+    assert_eq!(Drops::MAX.checked_add(Drops::from_drops(1).unwrap()), None);
+    assert_eq!(Drops::ZERO.checked_sub(drops), None);
+    assert_eq!(drops.checked_mul(2), Drops::from_drops(3_000_000));
+    assert_eq!(drops.checked_div(0), None);
+    assert_eq!(Drops::ZERO.saturating_sub(drops), Drops::ZERO);
+}
+
+#[test]
+fn issued_value_test() {
+    let value = |s: &str| s.parse::<IssuedValue>().unwrap();
+    assert_eq!(value("1").mantissa(), 1_000_000_000_000_000);
+    assert_eq!(value("1").exponent(), -15);
+    assert_eq!(value("-1.50").to_string(), "-1.5");
+    assert_eq!(value("15e-1"), value("1.5"));
+    assert_eq!(value("0.000").to_string(), "0");
+    assert_eq!(value("1e-30").to_string(), "1000000000000000e-45");
+    assert_eq!(value("1e16").to_string(), "1000000000000000e1");
+    assert_eq!(
+        value("1234567890123456789").to_string(),
+        "1234567890123456e3"
+    );
+    assert_eq!(value("0.00001").to_string(), "0.00001");
+    assert_eq!(value("1e-112"), IssuedValue::ZERO);
+    assert!("1e97".parse::<IssuedValue>().is_err());
+    assert!("1.".parse::<IssuedValue>().is_err());
+    assert!("01".parse::<IssuedValue>().is_err());
+    assert!("1e".parse::<IssuedValue>().is_err());
+
+    let one_third = value("1").checked_div(value("3")).unwrap();
+    assert_eq!(one_third.to_string(), "0.3333333333333333");
+    assert_eq!(
+        value("2").checked_div(value("3")).unwrap().to_string(),
+        "0.6666666666666667"
+    );
+    assert_eq!(
+        value("3").checked_mul(one_third).unwrap().to_string(),
+        "0.9999999999999999"
+    );
+    assert_eq!(
+        value("-2").checked_mul(value("2.5")).unwrap().to_string(),
+        "-5"
+    );
+    assert_eq!(value("1").checked_add(value("1e-20")).unwrap(), value("1"));
+    assert_eq!(
+        value("1.5").checked_sub(value("2.25")).unwrap().to_string(),
+        "-0.75"
+    );
+    assert_eq!(
+        value("1").checked_sub(value("1")).unwrap(),
+        IssuedValue::ZERO
+    );
+    assert_eq!(value("1").checked_div(IssuedValue::ZERO), None);
+    assert_eq!(value("9999999999999999e80").checked_mul(value("10")), None);
+
+    assert!(value("-2") < value("-1"));
+    assert!(value("-1") < IssuedValue::ZERO);
+    assert!(value("0.5") < value("2"));
+    assert_eq!(serde_json::to_string(&value("-0.75")).unwrap(), "\"-0.75\"");
+    assert_eq!(
+        serde_json::from_str::<IssuedValue>("\"1000000000000000e-96\"").unwrap(),
+        IssuedValue::new(1_000_000_000_000_000, -96).unwrap()
+    );
+    assert_eq!(value("-5").to_big_decimal(), BigDecimal::from(-5));
+}
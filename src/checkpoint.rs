@@ -0,0 +1,176 @@
+use crate::{LedgerScanner, NestedLedgerInfo, XRPClient};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The last ledger a consumer has fully processed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    ///
+    pub ledger_index: u64,
+    ///The hash of that ledger, the next ledger must have it as its `parent_hash`
+    pub hash: String,
+}
+
+/// Somewhere to keep the [`Checkpoint`] of a scan between restarts.
+pub trait CheckpointStore: std::fmt::Debug {
+    /// The last saved checkpoint, `None` if nothing was ever saved.
+    fn load(&self) -> Result<Option<Checkpoint>>;
+    /// Replaces the saved checkpoint.
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<()>;
+}
+
+/// Keeps the checkpoint in memory only, for tests and for scans that can start over.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoint: Option<Checkpoint>,
+}
+
+impl MemoryCheckpointStore {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self) -> Result<Option<Checkpoint>> {
+        Ok(self.checkpoint.clone())
+    }
+
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+/// Keeps the checkpoint as json in a file. Saving writes a temporary file next to it, syncs
+/// it to disk and renames it over the old one, then on Unix syncs the directory, so neither
+/// a crash nor a power loss leaves a half written checkpoint.
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// A store backed by the file at `path`, which does not need to exist yet.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileCheckpointStore { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self) -> Result<Option<Checkpoint>> {
+        match std::fs::read(&self.path) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec(checkpoint)?)?;
+        // On disk before the rename, or a power loss could leave the renamed file empty
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        // The rename itself is only durable once the directory is synced
+        #[cfg(unix)]
+        {
+            let directory = match self.path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => std::path::Path::new("."),
+            };
+            std::fs::File::open(directory)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`LedgerScanner`] that survives restarts. A ledger handed out by
+/// [`CheckpointedScanner::next_ledger`] is handed out again until the consumer calls
+/// [`CheckpointedScanner::ack`], which saves it as the checkpoint, so no ledger is skipped
+/// and no acknowledged ledger is replayed.
+#[derive(Debug)]
+pub struct CheckpointedScanner<S> {
+    scanner: LedgerScanner,
+    store: S,
+    pending: Option<NestedLedgerInfo>,
+}
+
+impl<S: CheckpointStore> CheckpointedScanner<S> {
+    /// Picks the scan up after the saved checkpoint, or starts it at `start_index` when the
+    /// store is empty.
+    pub fn resume(client: XRPClient, store: S, start_index: u64) -> Result<Self> {
+        let scanner = match store.load()? {
+            Some(checkpoint) => LedgerScanner::new(client, checkpoint.ledger_index + 1)
+                .with_previous_hash(checkpoint.hash),
+            None => LedgerScanner::new(client, start_index),
+        };
+        Ok(CheckpointedScanner {
+            scanner,
+            store,
+            pending: None,
+        })
+    }
+
+    /// Gives access to the scanner, to tune it before the scan starts.
+    pub fn scanner_mut(&mut self) -> &mut LedgerScanner {
+        &mut self.scanner
+    }
+
+    /// The ledger waiting to be acknowledged, or the next one when there is none.
+    pub async fn next_ledger(&mut self) -> Result<&NestedLedgerInfo> {
+        if self.pending.is_none() {
+            self.pending = Some(self.scanner.next_ledger().await?);
+        }
+        Ok(self
+            .pending
+            .as_ref()
+            .expect("The pending ledger was just set"))
+    }
+
+    /// Marks the pending ledger as processed and saves it as the checkpoint. If the store
+    /// fails the ledger stays pending.
+    pub fn ack(&mut self) -> Result<Checkpoint> {
+        let ledger = self
+            .pending
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("There is no ledger waiting to be acknowledged"))?;
+        let checkpoint = Checkpoint {
+            ledger_index: ledger
+                .ledger_index
+                .index()
+                .ok_or_else(|| anyhow::anyhow!("The pending ledger has no index"))?,
+            hash: ledger.hash.clone(),
+        };
+        self.store.save(&checkpoint)?;
+        self.pending = None;
+        Ok(checkpoint)
+    }
+
+    /// The last acknowledged ledger.
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>> {
+        self.store.load()
+    }
+}
+
+#[test]
+fn file_checkpoint_store_test() {
+    let path = std::env::temp_dir().join(format!("xrp-checkpoint-{}.json", std::process::id()));
+    let mut store = FileCheckpointStore::new(&path);
+    assert_eq!(store.load().unwrap(), None);
+    let checkpoint = Checkpoint {
+        ledger_index: 48293247,
+        hash: "C84E2AD5C98978C1B181CF117D0B824E98D3971BA0B2174AACA9D3A5835A99DA".into(),
+    };
+    store.save(&checkpoint).unwrap();
+    assert_eq!(
+        FileCheckpointStore::new(&path).load().unwrap(),
+        Some(checkpoint)
+    );
+    std::fs::remove_file(&path).unwrap();
+}
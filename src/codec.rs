@@ -0,0 +1,858 @@
+//! The canonical binary format of the ledger, the one of `tx_blob`, of binary `meta` and of
+//! ledger objects. The fields come from the same `definitions.json` as rippled's. See [1] for
+//! the source of the documentation.
+//!
+//! 1: https://xrpl.org/serialization.html
+
+use crate::address::{decode_account_id, encode_account_id};
+use crate::keys::sha512_half;
+use crate::IssuedValue;
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// The prefix of a signed transaction when it is hashed into its ID, "TXN\0".
+const TRANSACTION_ID_PREFIX: [u8; 4] = [0x54, 0x58, 0x4E, 0x00];
+const OBJECT_END_MARKER: u8 = 0xE1;
+const ARRAY_END_MARKER: u8 = 0xF1;
+const STOBJECT_TYPE_CODE: u16 = 14;
+const STARRAY_TYPE_CODE: u16 = 15;
+const END_MARKER_NTH: u16 = 1;
+const PATH_SEPARATOR: u8 = 0xFF;
+const PATH_SET_END: u8 = 0x00;
+const PATH_STEP_ACCOUNT: u8 = 0x01;
+const PATH_STEP_CURRENCY: u8 = 0x10;
+const PATH_STEP_ISSUER: u8 = 0x20;
+const AMOUNT_ISSUED_BIT: u64 = 0x8000_0000_0000_0000;
+const AMOUNT_POSITIVE_BIT: u64 = 0x4000_0000_0000_0000;
+const AMOUNT_MANTISSA_MASK: u64 = (1 << 54) - 1;
+/// Added to the exponent of an issued amount, so that it fits in 8 unsigned bits.
+const AMOUNT_EXPONENT_BIAS: i32 = 97;
+/// The largest length a variable length field can have.
+const MAX_VL_LENGTH: usize = 918_744;
+
+/// A field of `definitions.json`, as it is written there.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldDefinition {
+    nth: i32,
+    #[serde(rename = "isVLEncoded")]
+    is_vl_encoded: bool,
+    is_serialized: bool,
+    is_signing_field: bool,
+    #[serde(rename = "type")]
+    type_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct DefinitionsFile {
+    types: HashMap<String, i32>,
+    fields: Vec<(String, FieldDefinition)>,
+    transaction_types: HashMap<String, i32>,
+    ledger_entry_types: HashMap<String, i32>,
+    transaction_results: HashMap<String, i32>,
+}
+
+/// A field that makes it into the binary format.
+struct Field {
+    name: String,
+    type_name: String,
+    type_code: u16,
+    nth: u16,
+    is_vl_encoded: bool,
+    /// False for the signatures, which are left out of what is signed.
+    is_signing_field: bool,
+}
+
+/// The names of the values of a field that is written as a name in JSON, like
+/// `TransactionType`.
+struct Codes {
+    by_name: HashMap<String, i32>,
+    by_code: HashMap<i32, String>,
+}
+
+impl Codes {
+    fn new(by_name: HashMap<String, i32>) -> Self {
+        let by_code = by_name
+            .iter()
+            .map(|(name, code)| (*code, name.clone()))
+            .collect();
+        Codes { by_name, by_code }
+    }
+
+    fn code(&self, value: &Value) -> Option<i64> {
+        match value {
+            Value::String(name) => self.by_name.get(name).map(|code| i64::from(*code)),
+            value => value.as_i64(),
+        }
+    }
+
+    fn name(&self, code: i64) -> Value {
+        match self.by_code.get(&(code as i32)) {
+            Some(name) => Value::String(name.clone()),
+            None => Value::from(code),
+        }
+    }
+}
+
+struct Definitions {
+    fields: HashMap<String, Field>,
+    names_by_id: HashMap<(u16, u16), String>,
+    transaction_types: Codes,
+    ledger_entry_types: Codes,
+    transaction_results: Codes,
+}
+
+impl Definitions {
+    fn load() -> Self {
+        let file: DefinitionsFile = serde_json::from_str(include_str!("definitions.json"))
+            .expect("definitions.json is valid");
+        let mut fields = HashMap::new();
+        let mut names_by_id = HashMap::new();
+        for (name, definition) in file.fields {
+            let type_code = file.types.get(&definition.type_name).copied().unwrap_or(-1);
+            if !definition.is_serialized || definition.nth < 1 || type_code < 1 {
+                continue;
+            }
+            let field = Field {
+                name: name.clone(),
+                type_name: definition.type_name,
+                type_code: type_code as u16,
+                nth: definition.nth as u16,
+                is_vl_encoded: definition.is_vl_encoded,
+                is_signing_field: definition.is_signing_field,
+            };
+            names_by_id.insert((field.type_code, field.nth), name.clone());
+            fields.insert(name, field);
+        }
+        Definitions {
+            fields,
+            names_by_id,
+            transaction_types: Codes::new(file.transaction_types),
+            ledger_entry_types: Codes::new(file.ledger_entry_types),
+            transaction_results: Codes::new(file.transaction_results),
+        }
+    }
+
+    fn field_by_id(&self, type_code: u16, nth: u16) -> Option<&Field> {
+        self.names_by_id
+            .get(&(type_code, nth))
+            .and_then(|name| self.fields.get(name))
+    }
+
+    /// The names a field is written with in JSON, if it is one of those.
+    fn codes(&self, field: &Field) -> Option<&Codes> {
+        match field.name.as_str() {
+            "TransactionType" => Some(&self.transaction_types),
+            "LedgerEntryType" => Some(&self.ledger_entry_types),
+            "TransactionResult" => Some(&self.transaction_results),
+            _ => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref DEFINITIONS: Definitions = Definitions::load();
+}
+
+/// The binary form of a transaction, a ledger object or metadata, from its JSON the way
+/// rippled writes it. The fields that the definitions do not know, or that are never
+/// serialized like `hash`, are left out.
+pub fn to_binary<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    to_binary_fields(value, false)
+}
+
+/// The binary form of a transaction without its signatures, the part of what is signed that
+/// comes after the prefix.
+pub(crate) fn to_signing_binary<T: Serialize>(transaction: &T) -> Result<Vec<u8>> {
+    to_binary_fields(transaction, true)
+}
+
+fn to_binary_fields<T: Serialize>(value: &T, signing_only: bool) -> Result<Vec<u8>> {
+    let value = serde_json::to_value(value)?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| anyhow!("Only objects have a binary form, not {}", value))?;
+    let mut out = Vec::new();
+    write_object(&mut out, object, signing_only)?;
+    Ok(out)
+}
+
+/// Reads back what [`to_binary`] wrote, into any type that deserializes from rippled's JSON.
+pub fn from_binary<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let mut reader = Reader { bytes, position: 0 };
+    let object = read_object(&mut reader, false)?;
+    Ok(serde_json::from_value(Value::Object(object))?)
+}
+
+/// The hash, or ID, of a signed transaction from its binary form, in hex.
+pub fn transaction_hash(tx_blob: &[u8]) -> String {
+    let mut data = TRANSACTION_ID_PREFIX.to_vec();
+    data.extend_from_slice(tx_blob);
+    hex::encode_upper(sha512_half(&data))
+}
+
+/// Only the top level fields are left out when `signing_only` is set, like rippled does.
+fn write_object(out: &mut Vec<u8>, object: &Map<String, Value>, signing_only: bool) -> Result<()> {
+    let mut fields: Vec<(&Field, &Value)> = object
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .filter_map(|(name, value)| DEFINITIONS.fields.get(name).map(|field| (field, value)))
+        .filter(|(field, _)| field.is_signing_field || !signing_only)
+        .collect();
+    // The canonical order
+    fields.sort_by_key(|(field, _)| (field.type_code, field.nth));
+    for (field, value) in fields {
+        write_field(out, field, value)?;
+    }
+    Ok(())
+}
+
+fn write_field(out: &mut Vec<u8>, field: &Field, value: &Value) -> Result<()> {
+    write_field_id(out, field.type_code, field.nth);
+    if field.is_vl_encoded {
+        let mut bytes = Vec::new();
+        write_value(&mut bytes, field, value)?;
+        write_length(out, bytes.len())?;
+        out.extend_from_slice(&bytes);
+        Ok(())
+    } else {
+        write_value(out, field, value)
+    }
+}
+
+fn write_field_id(out: &mut Vec<u8>, type_code: u16, nth: u16) {
+    match (type_code < 16, nth < 16) {
+        (true, true) => out.push((type_code << 4 | nth) as u8),
+        (true, false) => out.extend_from_slice(&[(type_code << 4) as u8, nth as u8]),
+        (false, true) => out.extend_from_slice(&[nth as u8, type_code as u8]),
+        (false, false) => out.extend_from_slice(&[0, type_code as u8, nth as u8]),
+    }
+}
+
+/// The length prefix of a variable length field, one to three bytes.
+fn write_length(out: &mut Vec<u8>, length: usize) -> Result<()> {
+    if length <= 192 {
+        out.push(length as u8);
+    } else if length <= 12_480 {
+        let length = length - 193;
+        out.extend_from_slice(&[193 + (length >> 8) as u8, length as u8]);
+    } else if length <= MAX_VL_LENGTH {
+        let length = length - 12_481;
+        out.extend_from_slice(&[
+            241 + (length >> 16) as u8,
+            (length >> 8) as u8,
+            length as u8,
+        ]);
+    } else {
+        return Err(anyhow!("{} bytes is too long for a field", length));
+    }
+    Ok(())
+}
+
+fn write_value(out: &mut Vec<u8>, field: &Field, value: &Value) -> Result<()> {
+    let invalid = || {
+        anyhow!(
+            "{} is not a valid {}: {}",
+            field.name,
+            field.type_name,
+            value
+        )
+    };
+    match field.type_name.as_str() {
+        "UInt8" | "UInt16" | "UInt32" => {
+            let width = match field.type_name.as_str() {
+                "UInt8" => 1,
+                "UInt16" => 2,
+                _ => 4,
+            };
+            let number = match DEFINITIONS.codes(field) {
+                Some(codes) => codes.code(value).map(|code| code as u64),
+                None => as_u64(value),
+            }
+            .filter(|number| *number < 1 << (8 * width))
+            .ok_or_else(invalid)?;
+            out.extend_from_slice(&number.to_be_bytes()[8 - width..]);
+        }
+        "UInt64" => {
+            // In hex, a decimal would not survive the JSON numbers of other languages
+            let number = match value {
+                Value::String(s) if s.len() <= 16 => u64::from_str_radix(s, 16).ok(),
+                value => value.as_u64(),
+            }
+            .ok_or_else(invalid)?;
+            out.extend_from_slice(&number.to_be_bytes());
+        }
+        "Hash128" | "Hash160" | "Hash256" | "UInt96" | "UInt192" | "UInt384" | "UInt512" => {
+            let width = match field.type_name.as_str() {
+                "Hash128" => 16,
+                "Hash160" => 20,
+                "Hash256" => 32,
+                "UInt96" => 12,
+                "UInt192" => 24,
+                "UInt384" => 48,
+                _ => 64,
+            };
+            let bytes = hex_bytes(value).filter(|bytes| bytes.len() == width);
+            out.extend_from_slice(&bytes.ok_or_else(invalid)?);
+        }
+        "Blob" => out.extend_from_slice(&hex_bytes(value).ok_or_else(invalid)?),
+        "AccountID" => out.extend_from_slice(&account_id(value).ok_or_else(invalid)?),
+        "Amount" => write_amount(out, value).map_err(|e| anyhow!("{}: {}", invalid(), e))?,
+        "STObject" => {
+            write_object(out, value.as_object().ok_or_else(invalid)?, false)?;
+            out.push(OBJECT_END_MARKER);
+        }
+        "STArray" => {
+            for element in value.as_array().ok_or_else(invalid)? {
+                // Every element is an object with a single field, like `{"Memo": {...}}`
+                let wrapper = element.as_object().ok_or_else(invalid)?;
+                let mut present = wrapper.iter().filter(|(_, inner)| !inner.is_null());
+                let (name, inner) = match (present.next(), present.next()) {
+                    (Some(only), None) => only,
+                    _ => return Err(invalid()),
+                };
+                let inner_field = DEFINITIONS
+                    .fields
+                    .get(name)
+                    .filter(|inner_field| inner_field.type_code == STOBJECT_TYPE_CODE)
+                    .ok_or_else(|| anyhow!("{} is not an object of {}", name, field.name))?;
+                write_field(out, inner_field, inner)?;
+            }
+            out.push(ARRAY_END_MARKER);
+        }
+        "PathSet" => {
+            for (i, path) in value.as_array().ok_or_else(invalid)?.iter().enumerate() {
+                if i > 0 {
+                    out.push(PATH_SEPARATOR);
+                }
+                for step in path.as_array().ok_or_else(invalid)? {
+                    write_path_step(out, step).ok_or_else(invalid)?;
+                }
+            }
+            out.push(PATH_SET_END);
+        }
+        "Vector256" => {
+            for hash in value.as_array().ok_or_else(invalid)? {
+                let bytes = hex_bytes(hash).filter(|bytes| bytes.len() == 32);
+                out.extend_from_slice(&bytes.ok_or_else(invalid)?);
+            }
+        }
+        "Currency" => out.extend_from_slice(&currency_code(value).ok_or_else(invalid)?),
+        "Issue" => write_issue(out, value).ok_or_else(invalid)?,
+        "XChainBridge" => {
+            for (name, is_door) in &[
+                ("LockingChainDoor", true),
+                ("LockingChainIssue", false),
+                ("IssuingChainDoor", true),
+                ("IssuingChainIssue", false),
+            ] {
+                let part = value.get(name).ok_or_else(invalid)?;
+                if *is_door {
+                    out.push(20);
+                    out.extend_from_slice(&account_id(part).ok_or_else(invalid)?);
+                } else {
+                    write_issue(out, part).ok_or_else(invalid)?;
+                }
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "{} is a {}, which is not supported",
+                field.name,
+                field.type_name
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// A number, or a number in a string like `BigDecimal` serializes them.
+fn as_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_u64(),
+    }
+}
+
+fn hex_bytes(value: &Value) -> Option<Vec<u8>> {
+    hex::decode(value.as_str()?).ok()
+}
+
+fn account_id(value: &Value) -> Option<[u8; 20]> {
+    decode_account_id(value.as_str()?).ok()
+}
+
+/// XRP is a number of drops in a string, other currencies an object with a `value`, a
+/// `currency` and an `issuer`.
+fn write_amount(out: &mut Vec<u8>, value: &Value) -> Result<()> {
+    match value {
+        Value::String(drops) => {
+            let (positive, digits) = match drops.strip_prefix('-') {
+                Some(digits) => (false, digits),
+                None => (true, drops.as_str()),
+            };
+            let drops: u64 = digits
+                .parse()
+                .map_err(|_| anyhow!("not a number of drops"))?;
+            if drops & (AMOUNT_ISSUED_BIT | AMOUNT_POSITIVE_BIT) != 0 {
+                return Err(anyhow!("too many drops"));
+            }
+            let positive_bit = if positive { AMOUNT_POSITIVE_BIT } else { 0 };
+            out.extend_from_slice(&(drops | positive_bit).to_be_bytes());
+        }
+        Value::Object(amount) => {
+            let issued_value: IssuedValue = amount
+                .get("value")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("no value"))?
+                .parse()
+                .map_err(anyhow::Error::msg)?;
+            let mut bits = AMOUNT_ISSUED_BIT;
+            if !issued_value.is_zero() {
+                if !issued_value.is_negative() {
+                    bits |= AMOUNT_POSITIVE_BIT;
+                }
+                bits |= ((issued_value.exponent() + AMOUNT_EXPONENT_BIAS) as u64) << 54;
+                bits |= issued_value.mantissa();
+            }
+            out.extend_from_slice(&bits.to_be_bytes());
+            let currency = amount
+                .get("currency")
+                .ok_or_else(|| anyhow!("no currency"))?;
+            out.extend_from_slice(
+                &currency_code(currency).ok_or_else(|| anyhow!("not a currency"))?,
+            );
+            let issuer = amount.get("issuer").ok_or_else(|| anyhow!("no issuer"))?;
+            out.extend_from_slice(&account_id(issuer).ok_or_else(|| anyhow!("not an issuer"))?);
+        }
+        _ => return Err(anyhow!("neither drops nor an issued amount")),
+    }
+    Ok(())
+}
+
+/// The 20 bytes of a currency: zeros for XRP, a three letters code in bytes 12 to 14, or
+/// 40 hex digits.
+fn currency_code(value: &Value) -> Option<[u8; 20]> {
+    let code = value.as_str()?;
+    let mut bytes = [0; 20];
+    if code == "XRP" {
+        return Some(bytes);
+    }
+    if code.len() == 3 && code.bytes().all(|b| b.is_ascii_graphic()) {
+        bytes[12..15].copy_from_slice(code.as_bytes());
+    } else {
+        bytes.copy_from_slice(&hex::decode(code).ok().filter(|hex| hex.len() == 20)?);
+    }
+    Some(bytes)
+}
+
+fn write_issue(out: &mut Vec<u8>, value: &Value) -> Option<()> {
+    let currency = currency_code(value.get("currency")?)?;
+    out.extend_from_slice(&currency);
+    if currency != [0; 20] {
+        out.extend_from_slice(&account_id(value.get("issuer")?)?);
+    }
+    Some(())
+}
+
+fn write_path_step(out: &mut Vec<u8>, step: &Value) -> Option<()> {
+    let account = step.get("account").map(account_id);
+    let currency = step.get("currency").map(currency_code);
+    let issuer = step.get("issuer").map(account_id);
+    let mut step_type = 0;
+    for (part, bit) in &[
+        (account.is_some(), PATH_STEP_ACCOUNT),
+        (currency.is_some(), PATH_STEP_CURRENCY),
+        (issuer.is_some(), PATH_STEP_ISSUER),
+    ] {
+        if *part {
+            step_type |= bit;
+        }
+    }
+    out.push(step_type);
+    if let Some(account) = account {
+        out.extend_from_slice(&account?);
+    }
+    if let Some(currency) = currency {
+        out.extend_from_slice(&currency?);
+    }
+    if let Some(issuer) = issuer {
+        out.extend_from_slice(&issuer?);
+    }
+    Some(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+
+    fn read(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position + length;
+        let bytes = self.bytes.get(self.position..end).ok_or_else(|| {
+            anyhow!(
+                "The binary ends after {} bytes, {} more were expected",
+                self.bytes.len(),
+                end - self.bytes.len()
+            )
+        })?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read(8)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn read_20(&mut self) -> Result<[u8; 20]> {
+        let mut bytes = [0; 20];
+        bytes.copy_from_slice(self.read(20)?);
+        Ok(bytes)
+    }
+
+    fn read_field_id(&mut self) -> Result<(u16, u16)> {
+        let first = self.read_u8()?;
+        let mut type_code = u16::from(first >> 4);
+        let mut nth = u16::from(first & 0x0F);
+        if type_code == 0 {
+            type_code = u16::from(self.read_u8()?);
+        }
+        if nth == 0 {
+            nth = u16::from(self.read_u8()?);
+        }
+        Ok((type_code, nth))
+    }
+
+    fn read_length(&mut self) -> Result<usize> {
+        let first = usize::from(self.read_u8()?);
+        Ok(match first {
+            0..=192 => first,
+            193..=240 => 193 + ((first - 193) << 8) + usize::from(self.read_u8()?),
+            241..=254 => {
+                let second = usize::from(self.read_u8()?);
+                let third = usize::from(self.read_u8()?);
+                12_481 + ((first - 241) << 16) + (second << 8) + third
+            }
+            _ => return Err(anyhow!("{:#04X} does not start a length", first)),
+        })
+    }
+}
+
+/// The fields up to the end of the binary, or up to the end marker of a nested object.
+fn read_object(reader: &mut Reader, nested: bool) -> Result<Map<String, Value>> {
+    let mut object = Map::new();
+    loop {
+        if !nested && reader.is_empty() {
+            return Ok(object);
+        }
+        let (type_code, nth) = reader.read_field_id()?;
+        if nested && (type_code, nth) == (STOBJECT_TYPE_CODE, END_MARKER_NTH) {
+            return Ok(object);
+        }
+        let field = DEFINITIONS
+            .field_by_id(type_code, nth)
+            .ok_or_else(|| anyhow!("No field has type {} and code {}", type_code, nth))?;
+        let value = read_field(reader, field)?;
+        object.insert(field.name.clone(), value);
+    }
+}
+
+fn read_field(reader: &mut Reader, field: &Field) -> Result<Value> {
+    if field.is_vl_encoded {
+        let length = reader.read_length()?;
+        let mut inner = Reader {
+            bytes: reader.read(length)?,
+            position: 0,
+        };
+        let value = read_value(&mut inner, field)?;
+        if !inner.is_empty() {
+            return Err(anyhow!("{} is longer than its value", field.name));
+        }
+        Ok(value)
+    } else {
+        read_value(reader, field)
+    }
+}
+
+fn read_value(reader: &mut Reader, field: &Field) -> Result<Value> {
+    Ok(match field.type_name.as_str() {
+        "UInt8" | "UInt16" | "UInt32" => {
+            let width = match field.type_name.as_str() {
+                "UInt8" => 1,
+                "UInt16" => 2,
+                _ => 4,
+            };
+            let number = reader
+                .read(width)?
+                .iter()
+                .fold(0i64, |number, byte| number << 8 | i64::from(*byte));
+            match DEFINITIONS.codes(field) {
+                Some(codes) => codes.name(number),
+                None => Value::from(number),
+            }
+        }
+        "UInt64" => Value::String(format!("{:016X}", reader.read_u64()?)),
+        "Hash128" | "Hash160" | "Hash256" | "UInt96" | "UInt192" | "UInt384" | "UInt512" => {
+            let width = match field.type_name.as_str() {
+                "Hash128" => 16,
+                "Hash160" => 20,
+                "Hash256" => 32,
+                "UInt96" => 12,
+                "UInt192" => 24,
+                "UInt384" => 48,
+                _ => 64,
+            };
+            Value::String(hex::encode_upper(reader.read(width)?))
+        }
+        "Blob" => {
+            let rest = reader.bytes.len() - reader.position;
+            Value::String(hex::encode_upper(reader.read(rest)?))
+        }
+        "AccountID" => Value::String(encode_account_id(&reader.read_20()?)),
+        "Amount" => read_amount(reader)?,
+        "STObject" => Value::Object(read_object(reader, true)?),
+        "STArray" => {
+            let mut array = Vec::new();
+            loop {
+                let (type_code, nth) = reader.read_field_id()?;
+                if (type_code, nth) == (STARRAY_TYPE_CODE, END_MARKER_NTH) {
+                    break;
+                }
+                let inner_field = DEFINITIONS
+                    .field_by_id(type_code, nth)
+                    .filter(|inner_field| inner_field.type_code == STOBJECT_TYPE_CODE)
+                    .ok_or_else(|| anyhow!("{} holds something else than objects", field.name))?;
+                let mut wrapper = Map::new();
+                wrapper.insert(inner_field.name.clone(), read_field(reader, inner_field)?);
+                array.push(Value::Object(wrapper));
+            }
+            Value::Array(array)
+        }
+        "PathSet" => {
+            let mut paths = vec![Value::Array(Vec::new())];
+            loop {
+                match reader.read_u8()? {
+                    PATH_SET_END => break,
+                    PATH_SEPARATOR => paths.push(Value::Array(Vec::new())),
+                    step_type => {
+                        let step = read_path_step(reader, step_type)?;
+                        if let Some(Value::Array(path)) = paths.last_mut() {
+                            path.push(step);
+                        }
+                    }
+                }
+            }
+            Value::Array(paths)
+        }
+        "Vector256" => {
+            let mut hashes = Vec::new();
+            while !reader.is_empty() {
+                hashes.push(Value::String(hex::encode_upper(reader.read(32)?)));
+            }
+            Value::Array(hashes)
+        }
+        "Currency" => Value::String(currency_string(&reader.read_20()?)),
+        "Issue" => read_issue(reader)?,
+        "XChainBridge" => {
+            let mut bridge = Map::new();
+            for (name, is_door) in &[
+                ("LockingChainDoor", true),
+                ("LockingChainIssue", false),
+                ("IssuingChainDoor", true),
+                ("IssuingChainIssue", false),
+            ] {
+                let part = if *is_door {
+                    if reader.read_u8()? != 20 {
+                        return Err(anyhow!("{} is not an account", name));
+                    }
+                    Value::String(encode_account_id(&reader.read_20()?))
+                } else {
+                    read_issue(reader)?
+                };
+                bridge.insert(name.to_string(), part);
+            }
+            Value::Object(bridge)
+        }
+        _ => {
+            return Err(anyhow!(
+                "{} is a {}, which is not supported",
+                field.name,
+                field.type_name
+            ))
+        }
+    })
+}
+
+fn read_amount(reader: &mut Reader) -> Result<Value> {
+    let bits = reader.read_u64()?;
+    let positive = bits & AMOUNT_POSITIVE_BIT != 0;
+    if bits & AMOUNT_ISSUED_BIT == 0 {
+        let drops = bits & !AMOUNT_POSITIVE_BIT;
+        let sign = if positive || drops == 0 { "" } else { "-" };
+        return Ok(Value::String(format!("{}{}", sign, drops)));
+    }
+    let value = if bits == AMOUNT_ISSUED_BIT {
+        IssuedValue::ZERO
+    } else {
+        let mantissa = (bits & AMOUNT_MANTISSA_MASK) as i64;
+        let exponent = ((bits >> 54) & 0xFF) as i32 - AMOUNT_EXPONENT_BIAS;
+        IssuedValue::new(if positive { mantissa } else { -mantissa }, exponent)
+            .ok_or_else(|| anyhow!("{:016X} is not an issued amount", bits))?
+    };
+    let currency = currency_string(&reader.read_20()?);
+    let issuer = encode_account_id(&reader.read_20()?);
+    Ok(serde_json::json!({
+        "currency": currency,
+        "issuer": issuer,
+        "value": value.to_string(),
+    }))
+}
+
+/// A three letters code when the currency is one, its hex otherwise.
+fn currency_string(bytes: &[u8; 20]) -> String {
+    if *bytes == [0; 20] {
+        return "XRP".into();
+    }
+    let code = &bytes[12..15];
+    let is_standard = bytes
+        .iter()
+        .enumerate()
+        .all(|(i, byte)| (12..15).contains(&i) || *byte == 0);
+    if is_standard && code.iter().all(u8::is_ascii_graphic) && code != b"XRP" {
+        String::from_utf8_lossy(code).into_owned()
+    } else {
+        hex::encode_upper(bytes)
+    }
+}
+
+fn read_issue(reader: &mut Reader) -> Result<Value> {
+    let currency = reader.read_20()?;
+    if currency == [0; 20] {
+        return Ok(serde_json::json!({ "currency": "XRP" }));
+    }
+    Ok(serde_json::json!({
+        "currency": currency_string(&currency),
+        "issuer": encode_account_id(&reader.read_20()?),
+    }))
+}
+
+/// A step of a path, with the `type` rippled adds to say what the step holds.
+fn read_path_step(reader: &mut Reader, step_type: u8) -> Result<Value> {
+    let mut step = Map::new();
+    if step_type & PATH_STEP_ACCOUNT != 0 {
+        let account = encode_account_id(&reader.read_20()?);
+        step.insert("account".into(), Value::String(account));
+    }
+    if step_type & PATH_STEP_CURRENCY != 0 {
+        let currency = currency_string(&reader.read_20()?);
+        step.insert("currency".into(), Value::String(currency));
+    }
+    if step_type & PATH_STEP_ISSUER != 0 {
+        let issuer = encode_account_id(&reader.read_20()?);
+        step.insert("issuer".into(), Value::String(issuer));
+    }
+    step.insert("type".into(), Value::from(step_type));
+    step.insert(
+        "type_hex".into(),
+        Value::String(format!("{:016X}", step_type)),
+    );
+    Ok(Value::Object(step))
+}
+
+#[test]
+fn codec_test() {
+    let tx_blob = "1200002200000000240000003E6140000002540BE40068400000000000000A7321034AADB09CFF4A4804073701EC53C3510CDC95917C2BB0150FB742D0C66E6CEE9E74473045022022EB32AECEF7C644C891C19F87966DF9C62B1F34BABA6BE774325E4BB8E2DD62022100A51437898C28C2B297112DF8131F2BB39EA5FE613487DDD611525F17962646398114550FC62003E785DC231A1058A05E56E3F09CF4E68314D4CC8AB5B21D86A82C3E9E8D0ECF2404B77FECBA";
+    let tx: Value = from_binary(&hex::decode(tx_blob).unwrap()).unwrap();
+    assert_eq!(tx["TransactionType"], "Payment");
+    assert_eq!(tx["Account"], "r3kmLJN5D28dHuH8vZNUZpMC43pEHpaocV");
+    assert_eq!(tx["Amount"], "10000000000");
+    assert_eq!(tx["Sequence"], 62);
+    assert_eq!(hex::encode_upper(to_binary(&tx).unwrap()), tx_blob);
+    let transaction = crate::TransactionInfo::from_tx_blob(tx_blob).unwrap();
+    assert_eq!(
+        transaction.hash,
+        transaction_hash(&hex::decode(tx_blob).unwrap())
+    );
+    assert_eq!(transaction.to_tx_blob().unwrap(), tx_blob);
+
+    // An OfferCreate with an issued amount, signed with an ed25519 key
+    let tx = serde_json::json!({
+        "Account": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3",
+        "Fee": "10",
+        "Flags": 0,
+        "LastLedgerSequence": 16409087,
+        "Sequence": 16409064,
+        "SigningPubKey": "ED93BFA583E83331E9DC498DE4558CE4861ACFAB9385EBBC43BC56A0D9845A1DF2",
+        "TakerGets": "13100000",
+        "TakerPays": {
+            "currency": "USD",
+            "issuer": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3",
+            "value": "10"
+        },
+        "TransactionType": "OfferCreate",
+        "TxnSignature": "71135999783658A0CB4EBCF02E59ACD94C4D06D5BF909E05E6B97588155482BBA598535AD4728ACA1F90C4DE73FFC741B0A6AB87141BDA8BCC2F2DF9CD8C3703",
+        "hash": "not serialized"
+    });
+    let tx_blob = to_binary(&tx).unwrap();
+    assert_eq!(
+        transaction_hash(&tx_blob),
+        "66F3D6158CAB6E53405F8C264DB39F07D8D0454433A63DDFB98218ED1BC99B60"
+    );
+    let decoded: Value = from_binary(&tx_blob).unwrap();
+    assert_eq!(decoded["TakerPays"], tx["TakerPays"]);
+    assert!(decoded.get("hash").is_none());
+
+    // Metadata, with nested objects, arrays and the names of the results
+    let meta = serde_json::json!({
+        "AffectedNodes": [{
+            "ModifiedNode": {
+                "FinalFields": {
+                    "Balance": {
+                        "currency": "USD",
+                        "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji",
+                        "value": "-1.525330905250352"
+                    },
+                    "Flags": 1114112,
+                    "HighNode": "00000000000001E8",
+                    "LowNode": "0000000000000000"
+                },
+                "LedgerEntryType": "RippleState",
+                "LedgerIndex": "2F323020B4288ACD4066CC64C89DAD2E4D5DFC2D44571942A51C005BF79D6E25"
+            }
+        }],
+        "TransactionIndex": 3,
+        "TransactionResult": "tecPATH_PARTIAL"
+    });
+    let decoded: Value = from_binary(&to_binary(&meta).unwrap()).unwrap();
+    assert_eq!(decoded, meta);
+
+    let paths = serde_json::json!({
+        "Paths": [
+            [{ "account": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3", "type": 1, "type_hex": "0000000000000001" }],
+            [
+                { "currency": "EUR", "issuer": "rLyttXLh7Ttca9CMUaD3exVoXY2fn2zwj3", "type": 48, "type_hex": "0000000000000030" },
+                { "currency": "XRP", "type": 16, "type_hex": "0000000000000010" }
+            ]
+        ]
+    });
+    let decoded: Value = from_binary(&to_binary(&paths).unwrap()).unwrap();
+    assert_eq!(decoded, paths);
+    assert!(to_binary(&serde_json::json!({ "Sequence": -1 })).is_err());
+    assert!(from_binary::<Value>(&[0x24, 0x00]).is_err());
+}
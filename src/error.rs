@@ -0,0 +1,253 @@
+use serde_json::Value;
+use std::fmt;
+
+macro_rules! rippled_error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)*) => {
+        /// The `error` code of a failed rippled request. See [1]
+        /// 1: https://xrpl.org/error-formatting.html#universal-errors
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum RippledErrorCode {
+            $($(#[$doc])* $variant,)*
+            /// A code this crate does not know about
+            Other(String),
+        }
+
+        impl RippledErrorCode {
+            /// The code from the `error` field of an answer.
+            pub fn from_code(code: &str) -> Self {
+                match code {
+                    $($code => RippledErrorCode::$variant,)*
+                    other => RippledErrorCode::Other(other.to_string()),
+                }
+            }
+
+            /// The code as rippled spells it.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(RippledErrorCode::$variant => $code,)*
+                    RippledErrorCode::Other(other) => other,
+                }
+            }
+        }
+    };
+}
+
+rippled_error_codes! {
+    ///The address specified in the account field of the request does not correspond to an account in the ledger.
+    ActNotFound => "actNotFound",
+    ///The address specified is not a valid address.
+    ActMalformed => "actMalformed",
+    ///The server is amendment blocked and needs to be updated.
+    AmendmentBlocked => "amendmentBlocked",
+    ///The request contains improper syntax.
+    BadSyntax => "badSyntax",
+    ///The request references a ledger object that does not exist.
+    EntryNotFound => "entryNotFound",
+    ///The server does not allow the request from this client.
+    Forbidden => "forbidden",
+    ///The transaction cost exceeds what the server allows for a sign request.
+    HighFee => "highFee",
+    ///An internal error, possibly a bug in the server.
+    Internal => "internal",
+    ///One or more fields are specified incorrectly, or one or more required fields are missing.
+    InvalidParams => "invalidParams",
+    ///The request is not valid JSON.
+    JsonInvalid => "jsonInvalid",
+    ///The ledger index is malformed.
+    LgrIdxInvalid => "lgrIdxInvalid",
+    ///The ledger index range is malformed.
+    LgrIdxsInvalid => "lgrIdxsInvalid",
+    ///The ledger specified by the request does not exist, or the server does not have it.
+    LgrNotFound => "lgrNotFound",
+    ///The request does not have a command.
+    MissingCommand => "missingCommand",
+    ///The server does not have a closed ledger, typically because it has not finished starting up.
+    NoClosed => "noClosed",
+    ///The server does not know what the current ledger is, due to high load, network problems, validator failures, incorrect configuration, or some other problem.
+    NoCurrent => "noCurrent",
+    ///The server is having trouble connecting to the rest of the XRP Ledger peer-to-peer network.
+    NoNetwork => "noNetwork",
+    ///The client does not have the permission to do what was asked.
+    NoPermission => "noPermission",
+    ///The server does not implement the requested feature.
+    NotImpl => "notImpl",
+    ///The server is not ready to handle this request.
+    NotReady => "notReady",
+    ///The server is not synced to the network.
+    NotSynced => "notSynced",
+    ///The server has no API of the requested version.
+    InvalidApiVersion => "invalid_API_version",
+    ///The client is sending requests too quickly. The server is rate limiting this client.
+    SlowDown => "slowDown",
+    ///The server is under too much load to do this command right now.
+    TooBusy => "tooBusy",
+    ///The transaction is not in the ledgers the server has.
+    TxnNotFound => "txnNotFound",
+    ///The request does not contain a command that the server recognizes.
+    UnknownCmd => "unknownCmd",
+    ///The server accepts only text over the WebSocket.
+    WsTextRequired => "wsTextRequired",
+}
+
+impl fmt::Display for RippledErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Everything that can go wrong when asking a node something.
+#[derive(Debug)]
+pub enum XrpRpcError {
+    /// The node answered, with an error
+    Rippled {
+        ///
+        code: RippledErrorCode,
+        ///The human readable `error_message` of the answer
+        message: Option<String>,
+        ///The whole answer, some errors carry more fields, like `searched_all` for `txnNotFound`
+        response: Value,
+    },
+    /// The node answered with a failed HTTP status
+    Http {
+        ///
+        status: u16,
+    },
+    /// There was no answer, like a connection refused or reset
+    Transport(anyhow::Error),
+    /// The params could not be turned into json
+    Encode(serde_json::Error),
+    /// The answer could not be turned into the expected type
+    Decode {
+        ///
+        source: serde_json::Error,
+        ///The answer as it came
+        body: String,
+    },
+    /// The transaction cannot be completed or sent as it is, like one without an `Account`
+    InvalidTransaction(String),
+}
+
+impl XrpRpcError {
+    /// The error of an answer whose `status` is `error`, `None` for a successful answer.
+    pub fn from_response(response: &Value) -> Option<Self> {
+        if response.get("status").and_then(Value::as_str) != Some("error") {
+            return None;
+        }
+        Some(XrpRpcError::Rippled {
+            code: RippledErrorCode::from_code(
+                response
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+            ),
+            message: response
+                .get("error_message")
+                .and_then(Value::as_str)
+                .map(String::from),
+            response: response.clone(),
+        })
+    }
+
+    /// Sorts out a failure of the transport, keeping HTTP statuses apart from the rest.
+    pub fn from_transport(error: anyhow::Error) -> Self {
+        match error
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+        {
+            Some(status) => XrpRpcError::Http {
+                status: status.as_u16(),
+            },
+            None => XrpRpcError::Transport(error),
+        }
+    }
+
+    /// An answer that does not fit the expected type.
+    pub fn decode(source: serde_json::Error, body: &Value) -> Self {
+        XrpRpcError::Decode {
+            source,
+            body: body.to_string(),
+        }
+    }
+
+    /// Whether the connection to the node was reset or dropped while waiting for the answer.
+    pub fn is_connection_reset(&self) -> bool {
+        match self {
+            XrpRpcError::Transport(error) => error
+                .chain()
+                .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+                .any(|io_error| {
+                    matches!(
+                        io_error.kind(),
+                        std::io::ErrorKind::ConnectionReset
+                            | std::io::ErrorKind::ConnectionAborted
+                            | std::io::ErrorKind::BrokenPipe
+                            | std::io::ErrorKind::UnexpectedEof
+                    )
+                }),
+            _ => false,
+        }
+    }
+
+    /// The rippled error code, if the node answered with an error.
+    pub fn code(&self) -> Option<&RippledErrorCode> {
+        match self {
+            XrpRpcError::Rippled { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for XrpRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XrpRpcError::Rippled {
+                code,
+                message: Some(message),
+                ..
+            } => write!(f, "rippled error {}: {}", code, message),
+            XrpRpcError::Rippled { code, .. } => write!(f, "rippled error {}", code),
+            XrpRpcError::Http { status } => write!(f, "HTTP status {}", status),
+            XrpRpcError::Transport(error) => write!(f, "transport error: {}", error),
+            XrpRpcError::Encode(error) => write!(f, "could not encode the params: {}", error),
+            XrpRpcError::Decode { source, body } => {
+                write!(f, "could not decode {}: {}", body, source)
+            }
+            XrpRpcError::InvalidTransaction(reason) => write!(f, "invalid transaction: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for XrpRpcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XrpRpcError::Transport(error) => Some(error.as_ref()),
+            XrpRpcError::Encode(error) => Some(error),
+            XrpRpcError::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn rippled_error_test() {
+    let error = XrpRpcError::from_response(&serde_json::json!({
+        "account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+        "error": "actNotFound",
+        "error_code": 19,
+        "error_message": "Account not found.",
+        "ledger_current_index": 4,
+        "status": "error",
+        "validated": false
+    }))
+    .unwrap();
+    assert_eq!(error.code(), Some(&RippledErrorCode::ActNotFound));
+    assert_eq!(
+        error.to_string(),
+        "rippled error actNotFound: Account not found."
+    );
+    assert_eq!(
+        RippledErrorCode::from_code("someNewCode"),
+        RippledErrorCode::Other("someNewCode".into())
+    );
+    assert!(XrpRpcError::from_response(&serde_json::json!({ "status": "success" })).is_none());
+}
@@ -0,0 +1,254 @@
+use crate::{Drops, LaziedQueueData};
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Deserializer};
+
+/// The current state of the open-ledger requirements for the transaction cost. See [1] for
+/// the source of the documentation.
+///
+/// 1: https://xrpl.org/fee.html
+#[derive(Deserialize, Debug, Clone)]
+pub struct Fee {
+    ///Number of transactions provisionally included in the in-progress ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub current_ledger_size: u64,
+    ///Number of transactions currently queued for the next ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub current_queue_size: u64,
+    ///Various information about the transaction cost, in drops of XRP.
+    pub drops: FeeDrops,
+    ///The approximate number of transactions expected to be included in the current ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub expected_ledger_size: u64,
+    ///The Ledger Index of the current open ledger these stats describe.
+    pub ledger_current_index: u64,
+    ///Various information about the transaction cost, in fee levels.
+    pub levels: FeeLevels,
+    ///The maximum number of transactions that the transaction queue can currently hold.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub max_queue_size: u64,
+}
+
+///
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeeDrops {
+    ///The transaction cost required for a reference transaction to be included in a ledger under minimum load.
+    pub base_fee: Drops,
+    ///An approximation of the median transaction cost among transactions included in the previous validated ledger.
+    pub median_fee: Drops,
+    ///The minimum transaction cost for a reference transaction to be queued for a later ledger.
+    pub minimum_fee: Drops,
+    ///The minimum transaction cost that a reference transaction must pay to be included in the current open ledger.
+    pub open_ledger_fee: Drops,
+}
+
+///The costs as fee levels, which do not depend on the kind of transaction. The reference level is 256.
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeeLevels {
+    ///The median transaction cost among transactions in the previous validated ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub median_level: u64,
+    ///The minimum transaction cost required to be queued for a future ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub minimum_level: u64,
+    ///The minimum transaction cost required to be included in the current open ledger.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub open_ledger_level: u64,
+    ///The equivalent of the minimum transaction cost.
+    #[serde(deserialize_with = "u64_from_str")]
+    pub reference_level: u64,
+}
+
+/// rippled gives the numbers of `fee` as strings.
+fn u64_from_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrU64 {
+        Str(String),
+        U64(u64),
+    }
+    match StrOrU64::deserialize(deserializer)? {
+        StrOrU64::Str(s) => s.parse().map_err(serde::de::Error::custom),
+        StrOrU64::U64(n) => Ok(n),
+    }
+}
+
+/// How soon a transaction has to make it into a validated ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeUrgency {
+    /// Can wait in the queue for a few ledgers when the network is busy
+    Low,
+    /// Should make it into the open ledger
+    Normal,
+    /// Should make it into the open ledger even if more transactions come in before it
+    High,
+}
+
+/// Recommends a transaction cost from the answer of [`crate::XRPClient::fee`], following how
+/// rippled escalates the open ledger cost. See [1]
+///
+/// 1: https://xrpl.org/transaction-queue.html
+#[derive(Debug, Clone)]
+pub struct FeeEstimator {
+    fee: Fee,
+    queued_levels: Vec<u64>,
+    max_fee: Option<Drops>,
+}
+
+impl FeeEstimator {
+    /// How much the open ledger is assumed to grow before a [`FeeUrgency::High`] transaction
+    /// makes it in, as a fraction of the expected ledger size.
+    pub const HIGH_URGENCY_GROWTH: f64 = 0.25;
+
+    /// An estimator for the network as described by `fee`.
+    pub fn new(fee: Fee) -> Self {
+        FeeEstimator {
+            fee,
+            queued_levels: Vec::new(),
+            max_fee: None,
+        }
+    }
+
+    /// Takes into account the transactions the sending account already has in the queue,
+    /// from `account_info` with `queue` set. rippled only lets a transaction into the open
+    /// ledger ahead of them if its fee also pays for the ones queued before it.
+    pub fn with_queue(mut self, queue_data: &LaziedQueueData) -> Self {
+        self.queued_levels = queue_data
+            .transactions
+            .iter()
+            .flatten()
+            .filter_map(|transaction| transaction.fee_level.to_u64())
+            .collect();
+        self
+    }
+
+    /// Never recommends more than `max_fee`.
+    pub fn with_max_fee(mut self, max_fee: Drops) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// The transaction cost to pay for a reference transaction.
+    pub fn recommend(&self, urgency: FeeUrgency) -> Drops {
+        let levels = &self.fee.levels;
+        let level = match urgency {
+            FeeUrgency::Low => levels.minimum_level.max(levels.reference_level),
+            FeeUrgency::Normal => self.with_queued(levels.open_ledger_level),
+            FeeUrgency::High => {
+                let growth = (self.fee.expected_ledger_size as f64 * Self::HIGH_URGENCY_GROWTH)
+                    .ceil() as u64;
+                self.with_queued(self.escalated_level(growth.max(1)))
+            }
+        };
+        let drops = self.level_to_drops(level).max(self.fee.drops.base_fee);
+        match self.max_fee {
+            Some(max_fee) => drops.min(max_fee),
+            None => drops,
+        }
+    }
+
+    /// The open ledger cost once `growth` more transactions made it in. Past its expected
+    /// size the cost of the open ledger grows with the square of its size, starting from the
+    /// median level of the last ledger.
+    fn escalated_level(&self, growth: u64) -> u64 {
+        let levels = &self.fee.levels;
+        let expected = u128::from(self.fee.expected_ledger_size.max(1));
+        let current = u128::from(self.fee.current_ledger_size);
+        let size = current + u128::from(growth);
+        if size <= expected {
+            return levels.open_ledger_level;
+        }
+        let from_median = u128::from(levels.median_level) * size.pow(2) / expected.pow(2);
+        let from_open =
+            u128::from(levels.open_ledger_level) * size.pow(2) / current.max(expected).pow(2);
+        from_median
+            .max(from_open)
+            .max(u128::from(levels.open_ledger_level))
+            .min(u128::from(u64::MAX)) as u64
+    }
+
+    /// The level that also pays the shortfall of the account's queued transactions.
+    fn with_queued(&self, level: u64) -> u64 {
+        self.queued_levels
+            .iter()
+            .filter(|queued| **queued < level)
+            .fold(level, |total, queued| total.saturating_add(level - queued))
+    }
+
+    /// Rounded up, so that paying it does not fall short of the level.
+    fn level_to_drops(&self, level: u64) -> Drops {
+        let reference_level = u128::from(self.fee.levels.reference_level.max(1));
+        let scaled = u128::from(level) * u128::from(self.fee.drops.base_fee.drops());
+        let mut drops = scaled / reference_level;
+        if drops * reference_level < scaled {
+            drops += 1;
+        }
+        Drops::from_drops(drops.min(u128::from(u64::MAX)) as u64).unwrap_or(Drops::MAX)
+    }
+}
+
+#[test]
+fn fee_estimator_test() {
+    let fee: Fee = serde_json::from_str(
+        r#"{
+            "current_ledger_size": "56",
+            "current_queue_size": "11",
+            "drops": {
+                "base_fee": "10",
+                "median_fee": "10000",
+                "minimum_fee": "10",
+                "open_ledger_fee": "2653937"
+            },
+            "expected_ledger_size": "55",
+            "ledger_current_index": 26575101,
+            "levels": {
+                "median_level": "256000",
+                "minimum_level": "256",
+                "open_ledger_level": "67940792",
+                "reference_level": "256"
+            },
+            "max_queue_size": "1100",
+            "status": "success"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        fee.drops.open_ledger_fee,
+        Drops::from_drops(2653937).unwrap()
+    );
+    let estimator = FeeEstimator::new(fee);
+    assert_eq!(estimator.recommend(FeeUrgency::Low).drops(), 10);
+    // Rounded up, so that the fee level is not short of the open ledger level
+    assert_eq!(estimator.recommend(FeeUrgency::Normal).drops(), 2653938);
+    assert!(estimator.recommend(FeeUrgency::High).drops() > 2653938);
+    assert_eq!(
+        estimator
+            .clone()
+            .with_max_fee(Drops::from_drops(100000).unwrap())
+            .recommend(FeeUrgency::High)
+            .drops(),
+        100000
+    );
+
+    let queue_data: LaziedQueueData = serde_json::from_str(
+        r#"{
+            "auth_change_queued": false,
+            "highest_sequence": 10,
+            "lowest_sequence": 10,
+            "max_spend_drops_total": "100",
+            "transactions": [
+                {
+                    "auth_change": false,
+                    "fee": "10",
+                    "fee_level": "256",
+                    "max_spend_drops": "100",
+                    "seq": 10
+                }
+            ],
+            "txn_count": 1
+        }"#,
+    )
+    .unwrap();
+    let estimator = estimator.with_queue(&queue_data);
+    assert_eq!(estimator.recommend(FeeUrgency::Low).drops(), 10);
+    assert_eq!(estimator.recommend(FeeUrgency::Normal).drops(), 5307865);
+}
@@ -0,0 +1,398 @@
+//! Keys the way rippled makes them: family seeds, the keypairs they derive, and the account
+//! IDs of public keys. See [1] for the source of the documentation.
+//!
+//! 1: https://xrpl.org/cryptographic-keys.html
+
+use crate::address::{decode_check, encode_check};
+use crate::Account;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+const SECP256K1_SEED_PREFIX: [u8; 1] = [0x21];
+const ED25519_SEED_PREFIX: [u8; 3] = [0x01, 0xE1, 0x4B];
+const ED25519_KEY_PREFIX: u8 = 0xED;
+
+/// The first half of the sha512, the hash the ledger uses nearly everywhere.
+pub(crate) fn sha512_half(data: &[u8]) -> [u8; 32] {
+    let mut half = [0; 32];
+    half.copy_from_slice(&Sha512::digest(data)[..32]);
+    half
+}
+
+/// The signing algorithms of the ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    /// The original algorithm, and the default of `wallet_propose`
+    Secp256k1,
+    ///
+    Ed25519,
+}
+
+/// The 16 bytes every key of a wallet comes from, written like `snoPBrXtMeMyMHUVTgbuqAfg1SUTb`
+/// for secp256k1 and `sEd...` for ed25519. Keep it secret: its `Debug` does not show it, but
+/// its `Display` does.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Seed {
+    bytes: [u8; 16],
+    key_type: KeyType,
+}
+
+impl Seed {
+    /// A seed from its raw bytes.
+    pub fn from_bytes(bytes: [u8; 16], key_type: KeyType) -> Self {
+        Seed { bytes, key_type }
+    }
+
+    /// A new seed from the operating system's randomness.
+    pub fn random(key_type: KeyType) -> Self {
+        let mut bytes = [0; 16];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut bytes);
+        Seed { bytes, key_type }
+    }
+
+    /// The algorithm of the keys of the seed.
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// The keypair of the first account of the seed, the one `wallet_propose` gives.
+    pub fn derive_keypair(&self) -> Keypair {
+        match self.key_type {
+            KeyType::Ed25519 => {
+                let secret = ed25519_dalek::SecretKey::from_bytes(&sha512_half(&self.bytes))
+                    .expect("Any 32 bytes are an ed25519 secret key");
+                let mut public_key = [0; 33];
+                public_key[0] = ED25519_KEY_PREFIX;
+                public_key[1..].copy_from_slice(ed25519_dalek::PublicKey::from(&secret).as_bytes());
+                Keypair {
+                    public_key: PublicKey(public_key),
+                    private_key: secret.to_bytes(),
+                }
+            }
+            KeyType::Secp256k1 => {
+                let secp = secp256k1::Secp256k1::signing_only();
+                let root_private = secp256k1_scalar(&self.bytes);
+                let root_public = secp256k1::PublicKey::from_secret_key(&secp, &root_private);
+                // The first account of the family, account index 0
+                let mut material = root_public.serialize().to_vec();
+                material.extend_from_slice(&0u32.to_be_bytes());
+                let mut private = secp256k1_scalar(&material);
+                private
+                    .add_assign(&root_private[..])
+                    .expect("The sum of two scalars of the family is a valid key");
+                Keypair {
+                    public_key: PublicKey(
+                        secp256k1::PublicKey::from_secret_key(&secp, &private).serialize(),
+                    ),
+                    private_key: {
+                        let mut bytes = [0; 32];
+                        bytes.copy_from_slice(&private[..]);
+                        bytes
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// The first `sha512_half(material || sequence)` that is a valid secp256k1 secret key.
+fn secp256k1_scalar(material: &[u8]) -> secp256k1::SecretKey {
+    (0u32..)
+        .find_map(|sequence| {
+            let mut data = material.to_vec();
+            data.extend_from_slice(&sequence.to_be_bytes());
+            secp256k1::SecretKey::from_slice(&sha512_half(&data)).ok()
+        })
+        .expect("Some hash is a valid secp256k1 secret key")
+}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Seed")
+            .field("key_type", &self.key_type)
+            .finish()
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut payload = match self.key_type {
+            KeyType::Secp256k1 => SECP256K1_SEED_PREFIX.to_vec(),
+            KeyType::Ed25519 => ED25519_SEED_PREFIX.to_vec(),
+        };
+        payload.extend_from_slice(&self.bytes);
+        f.write_str(&encode_check(&payload))
+    }
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let payload = decode_check(s).map_err(|_| "The seed is not valid base58".to_string())?;
+        let (key_type, bytes) = if payload.len() == 17 && payload[..1] == SECP256K1_SEED_PREFIX {
+            (KeyType::Secp256k1, &payload[1..])
+        } else if payload.len() == 19 && payload[..3] == ED25519_SEED_PREFIX {
+            (KeyType::Ed25519, &payload[3..])
+        } else {
+            return Err("The string is not a family seed".into());
+        };
+        let mut seed = [0; 16];
+        seed.copy_from_slice(bytes);
+        Ok(Seed::from_bytes(seed, key_type))
+    }
+}
+
+/// A public key, 33 bytes: compressed secp256k1, or 0xED then ed25519. It is what
+/// `SigningPubKey` holds, in hex.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; 33]);
+
+impl PublicKey {
+    /// A public key from its bytes, `None` if it is not one of the ledger's.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes.first() {
+            Some(0x02) | Some(0x03) | Some(&ED25519_KEY_PREFIX) if bytes.len() == 33 => {
+                let mut key = [0; 33];
+                key.copy_from_slice(bytes);
+                Some(PublicKey(key))
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    pub fn as_bytes(&self) -> &[u8; 33] {
+        &self.0
+    }
+
+    ///
+    pub fn key_type(&self) -> KeyType {
+        if self.0[0] == ED25519_KEY_PREFIX {
+            KeyType::Ed25519
+        } else {
+            KeyType::Secp256k1
+        }
+    }
+
+    /// Whether `signature` is the signature of `message` by the private key of this key, see
+    /// [`Keypair::sign`].
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self.key_type() {
+            KeyType::Ed25519 => {
+                let public_key = ed25519_dalek::PublicKey::from_bytes(&self.0[1..]);
+                let signature = ed25519_dalek::Signature::try_from(signature);
+                match (public_key, signature) {
+                    (Ok(public_key), Ok(signature)) => {
+                        public_key.verify_strict(message, &signature).is_ok()
+                    }
+                    _ => false,
+                }
+            }
+            KeyType::Secp256k1 => {
+                let message = secp256k1::Message::from_slice(&sha512_half(message));
+                let public_key = secp256k1::PublicKey::from_slice(&self.0);
+                let signature = secp256k1::Signature::from_der(signature);
+                match (message, public_key, signature) {
+                    (Ok(message), Ok(public_key), Ok(signature)) => {
+                        // Only the canonical low S form, like rippled
+                        secp256k1::Secp256k1::verification_only()
+                            .verify(&message, &signature, &public_key)
+                            .is_ok()
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    /// The account the key is the master key of: the RIPEMD-160 of the SHA-256 of the key.
+    pub fn account(&self) -> Account {
+        let mut account_id = [0; 20];
+        account_id.copy_from_slice(&Ripemd160::digest(&Sha256::digest(&self.0)));
+        Account::from_account_id(&account_id)
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    /// Upper case hex, like the ledger writes it.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&hex::encode_upper(&self.0[..]))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = String;
+
+    /// Parses the hex of a key, like a `SigningPubKey`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|e| format!("{:?} is not hex: {}", s, e))?;
+        Self::from_bytes(&bytes).ok_or_else(|| format!("{:?} is not a public key", s))
+    }
+}
+
+/// A public key with its private key. Its `Debug` does not show the private key.
+#[derive(Clone)]
+pub struct Keypair {
+    public_key: PublicKey,
+    private_key: [u8; 32],
+}
+
+impl Keypair {
+    ///
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// The raw secret: the secp256k1 scalar or the ed25519 secret key.
+    pub fn private_key(&self) -> &[u8; 32] {
+        &self.private_key
+    }
+
+    ///
+    pub fn key_type(&self) -> KeyType {
+        self.public_key.key_type()
+    }
+
+    /// The account the keypair is the master key of.
+    pub fn account(&self) -> Account {
+        self.public_key.account()
+    }
+
+    /// Signs `message` like rippled: ed25519 signs the message itself, secp256k1 signs its
+    /// [`sha512_half`] and gives the signature in DER, with the canonical low S.
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self.key_type() {
+            KeyType::Ed25519 => {
+                let secret = ed25519_dalek::SecretKey::from_bytes(&self.private_key)
+                    .expect("Any 32 bytes are an ed25519 secret key");
+                let public_key = ed25519_dalek::PublicKey::from(&secret);
+                ed25519_dalek::ExpandedSecretKey::from(&secret)
+                    .sign(message, &public_key)
+                    .to_bytes()
+                    .to_vec()
+            }
+            KeyType::Secp256k1 => {
+                let message = secp256k1::Message::from_slice(&sha512_half(message))
+                    .expect("A hash is 32 bytes");
+                let private_key = secp256k1::SecretKey::from_slice(&self.private_key)
+                    .expect("The keypair holds a valid secp256k1 key");
+                // libsecp256k1 only makes low S signatures
+                secp256k1::Secp256k1::signing_only()
+                    .sign(&message, &private_key)
+                    .serialize_der()
+                    .to_vec()
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+/// A seed with the keys and the account it derives, what `wallet_propose` gives.
+#[derive(Debug, Clone)]
+pub struct Wallet {
+    seed: Seed,
+    keypair: Keypair,
+}
+
+impl Wallet {
+    /// A new wallet from a random seed.
+    pub fn generate(key_type: KeyType) -> Self {
+        Self::from_seed(Seed::random(key_type))
+    }
+
+    ///
+    pub fn from_seed(seed: Seed) -> Self {
+        let keypair = seed.derive_keypair();
+        Wallet { seed, keypair }
+    }
+
+    ///
+    pub fn seed(&self) -> &Seed {
+        &self.seed
+    }
+
+    ///
+    pub fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+
+    ///
+    pub fn account(&self) -> Account {
+        self.keypair.account()
+    }
+}
+
+#[test]
+fn keys_test() {
+    // The "masterpassphrase" genesis account
+    let seed: Seed = "snoPBrXtMeMyMHUVTgbuqAfg1SUTb".parse().unwrap();
+    assert_eq!(seed.to_string(), "snoPBrXtMeMyMHUVTgbuqAfg1SUTb");
+    let wallet = Wallet::from_seed(seed);
+    assert_eq!(
+        wallet.keypair().public_key().to_string(),
+        "0330E7FC9D56BB25D6893BA3F317AE5BCF33B3291BD63DB32654A313222F7FD020"
+    );
+    assert_eq!(
+        wallet.account(),
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap()
+    );
+    assert!(!format!("{:?}", wallet).contains("snoPBrXtMeMyMHUVTgbuqAfg1SUTb"));
+
+    let wallet = Wallet::generate(KeyType::Ed25519);
+    assert!(wallet.seed().to_string().starts_with("sEd"));
+    let public_key: PublicKey = wallet.keypair().public_key().to_string().parse().unwrap();
+    assert_eq!(public_key.key_type(), KeyType::Ed25519);
+    assert_eq!(public_key.account(), wallet.account());
+    let seed: Seed = wallet.seed().to_string().parse().unwrap();
+    assert_eq!(seed.derive_keypair().public_key(), &public_key);
+    assert!("0330E7".parse::<PublicKey>().is_err());
+
+    // The signing vectors of ripple-keypairs
+    let message = b"test message";
+    let keypair = "sp5fghtJtpUorTwvof1NpDXAzNwf5"
+        .parse::<Seed>()
+        .unwrap()
+        .derive_keypair();
+    assert_eq!(
+        keypair.public_key().to_string(),
+        "030D58EB48B4420B1F7B9DF55087E0E29FEF0E8468F9A6825B01CA2C361042D435"
+    );
+    let signature = keypair.sign(message);
+    assert_eq!(
+        hex::encode_upper(&signature),
+        "30440220583A91C95E54E6A651C47BEC22744E0B101E2C4060E7B08F6341657DAD9BC3EE02207D1489C7395DB0188D3A56A977ECBA54B36FA9371B40319655B1B4429E33EF2D"
+    );
+    assert!(keypair.public_key().verify(message, &signature));
+    assert!(!keypair.public_key().verify(b"other message", &signature));
+    let keypair = "sEdSKaCy2JT7JaM7v95H9SxkhP9wS2r"
+        .parse::<Seed>()
+        .unwrap()
+        .derive_keypair();
+    assert_eq!(
+        keypair.public_key().to_string(),
+        "ED01FA53FA5A7E77798F882ECE20B1ABC00BB358A9E55A202D0D0676BD0CE37A63"
+    );
+    let signature = keypair.sign(message);
+    assert_eq!(
+        hex::encode_upper(&signature),
+        "CB199E1BFD4E3DAA105E4832EEDFA36413E1F44205E4EFB9E27E826044C21E3E2E848BBC8195E8959BADF887599B7310AD1B7047EF11B682E0D068F73749750E"
+    );
+    assert!(keypair.public_key().verify(message, &signature));
+}
@@ -18,17 +18,66 @@
 
 use anyhow::Result;
 use bigdecimal::BigDecimal;
+use futures::stream::{self, Stream};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::str::FromStr;
-use throttled_json_rpc::{ClientAuth, ClientOptions, ReqBatcher, RPS};
+use std::sync::Arc;
+
+mod address;
+mod amount;
+mod checkpoint;
+mod codec;
+mod error;
+mod fee;
+mod keys;
+mod objects;
+mod pool;
+mod retry;
+mod scanner;
+mod sequence;
+mod server;
+mod signing;
+mod submit;
+mod transactions;
+mod transport;
+mod ws;
+pub use address::XAddress;
+pub use amount::{Drops, IssuedValue};
+pub use checkpoint::{
+    Checkpoint, CheckpointStore, CheckpointedScanner, FileCheckpointStore, MemoryCheckpointStore,
+};
+pub use codec::{from_binary, to_binary, transaction_hash};
+pub use error::{RippledErrorCode, XrpRpcError};
+pub use fee::{Fee, FeeDrops, FeeEstimator, FeeLevels, FeeUrgency};
+pub use keys::{KeyType, Keypair, PublicKey, Seed, Wallet};
+pub use objects::*;
+pub use pool::{NodeHealth, NodePool};
+pub use retry::{RetryOn, RetryPolicy};
+pub use scanner::{ChainBreak, LedgerScanner, ScanError};
+pub use sequence::{AccountSequence, SequenceManager};
+pub use server::{
+    parse_complete_ledgers, LastClose, NestedServerInfo, NestedServerState, OperatingMode,
+    ServerInfo, ServerInfoValidatedLedger, ServerState, ServerStateValidatedLedger,
+};
+pub use signing::{
+    combine_signers, multisign_transaction, sign_transaction, MultisignedTransaction,
+    SignedTransaction, Signer, SignerWrapper,
+};
+pub use submit::{EngineResult, ReliableSubmitter, ResultCategory, Submit, SubmitOutcome};
+pub use transactions::*;
+pub use transport::{MockTransport, ReqBatcherTransport, Transport};
+pub use ws::{Book, LedgerClosed, TransactionMessage, XRPWebSocketClient};
 
 /// A balance for xrp could be just the token or a value in
 /// some other currency.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum Balance {
     /// The value of just the token
-    XRP(BigDecimal),
+    XRP(Drops),
     /// Value of the other currencies
     Other {
         /// The currency is a currency code
@@ -36,14 +85,16 @@ pub enum Balance {
         ///
         issuer: String,
         /// Not in atomic but the canonic values of the currency
-        value: BigDecimal,
+        value: IssuedValue,
     },
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
 /**
 * Starts with r
 * Length is 25-35 chars in length
+* Base58 with the ledger's alphabet, a 0x00 type prefix and a double sha256 checksum
 * 1: https://xrpl.org/basic-data-types.html#addresses
 */
 pub struct Account(String);
@@ -62,9 +113,41 @@ fn account_validate(s: &str) -> Result<String, String> {
     if s.len() > MAX_LENGTH {
         return Err(format!("{:?} is longer than {} chars ", s, MAX_LENGTH));
     }
+    address::decode_account_id(s)?;
     Ok(s.into())
 }
 
+impl Account {
+    /// The account of a 20 bytes account ID, as found in binary transactions.
+    pub fn from_account_id(account_id: &[u8; 20]) -> Self {
+        Account(address::encode_account_id(account_id))
+    }
+
+    /// The account a public key is the master key of.
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        public_key.account()
+    }
+
+    /// The 20 bytes account ID the address encodes.
+    pub fn account_id(&self) -> [u8; 20] {
+        address::decode_account_id(&self.0).expect("An account holds a valid address")
+    }
+}
+
+impl std::fmt::Display for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::convert::TryFrom<String> for Account {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        account_validate(&s).map(Account)
+    }
+}
+
 impl FromStr for Account {
     type Err = String;
 
@@ -111,6 +194,71 @@ pub struct AccountTxParams<'a, 'b> {
     pub forward: Option<bool>,
     /// Default varies. Limit the number of transactions to retrieve. The server is not required to honor this value.
     pub limit: Option<u64>,
+    ///Value from a previous paginated response. Resume retrieving data where that response left off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<serde_json::Value>,
+}
+
+///https://xrpl.org/account_lines.html
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountLinesParams<'a, 'b> {
+    ///
+    pub account: &'a Account,
+    ///The Address of a second account. If provided, show only lines of trust connecting the two accounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer: Option<&'a Account>,
+    ///A 20-byte hex string for the ledger version to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<&'b str>,
+    ///The ledger index of the ledger to use, or a shortcut string to choose a ledger automatically
+    #[serde(flatten)]
+    pub ledger_index: Option<LedgerIndex>,
+    ///Limit the number of trust lines to retrieve. The server is not required to honor this value. Must be within the inclusive range 10 to 400.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    ///Value from a previous paginated response. Resume retrieving data where that response left off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<serde_json::Value>,
+}
+
+///https://xrpl.org/account_objects.html
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountObjectsParams<'a, 'b> {
+    ///
+    pub account: &'a Account,
+    ///If included, filter results to include only this type of ledger object.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<AccountObjectType>,
+    ///If true, the response only includes objects that would block this account from being deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_blockers_only: Option<bool>,
+    ///A 20-byte hex string for the ledger version to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<&'b str>,
+    ///The ledger index of the ledger to use, or a shortcut string to choose a ledger automatically
+    #[serde(flatten)]
+    pub ledger_index: Option<LedgerIndex>,
+    ///The maximum number of objects to include in the results. Must be within the inclusive range 10 to 400.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    ///Value from a previous paginated response. Resume retrieving data where that response left off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<serde_json::Value>,
+}
+
+///https://xrpl.org/tx.html
+#[derive(Serialize, Debug, Clone)]
+pub struct TxParams<'a> {
+    ///The 256-bit hash of the transaction, as hex.
+    pub transaction: &'a str,
+    ///If true, return transaction data and metadata as binary serialized to hexadecimal strings.
+    pub binary: bool,
+    ///Use this with max_ledger to specify a range of up to 1000 ledger indexes, starting with this ledger (inclusive). If the server cannot find the transaction, it confirms whether it was able to search all the ledgers in this range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_ledger: Option<u32>,
+    ///Use this with min_ledger to specify a range of up to 1000 ledger indexes, ending with this ledger (inclusive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ledger: Option<u32>,
 }
 
 ///https://xrpl.org/ledger.html
@@ -137,11 +285,72 @@ pub struct LedgerInfoParams {
     pub queue: Option<bool>,
 }
 
-///
-#[derive(Deserialize, Debug)]
+///https://xrpl.org/ledger-object-types.html
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LedgerEntryType {
     ///
     AccountRoot, // WHY DOES THIS EVEN EXIST???
+    ///
+    Amendments,
+    ///
+    AMM,
+    ///
+    Check,
+    ///
+    DepositPreauth,
+    ///
+    DirectoryNode,
+    ///
+    Escrow,
+    ///
+    FeeSettings,
+    ///
+    LedgerHashes,
+    ///
+    NegativeUNL,
+    ///
+    NFTokenOffer,
+    ///
+    NFTokenPage,
+    ///
+    Offer,
+    ///
+    PayChannel,
+    ///
+    RippleState,
+    ///
+    SignerList,
+    ///
+    Ticket,
+}
+
+///The `type` filter of account_objects, see [1]
+/// 1: https://xrpl.org/account_objects.html
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountObjectType {
+    ///
+    Amm,
+    ///
+    Check,
+    ///
+    DepositPreauth,
+    ///
+    Escrow,
+    ///
+    NftOffer,
+    ///
+    NftPage,
+    ///
+    Offer,
+    ///
+    PaymentChannel,
+    ///
+    SignerList,
+    ///Trust lines, the RippleState objects
+    State,
+    ///
+    Ticket,
 }
 
 ///https://xrpl.org/accountroot.html
@@ -150,7 +359,7 @@ pub struct AccountData {
     ///
     pub Account: String,
     ///
-    pub Balance: BigDecimal,
+    pub Balance: Drops,
     ///https://xrpl.org/accountroot.html#accountroot-flags
     ///There are several options which can be either enabled or disabled for an account. These options can be changed with an AccountSet transaction. In the ledger, flags are represented as binary values that can be combined with bitwise-or operations. The bit values for the flags in the ledger are different than the values used to enable or disable those flags in a transaction. Ledger flags have names that begin with lsf.
     pub Flags: Option<BigDecimal>,
@@ -162,6 +371,8 @@ pub struct AccountData {
     pub PreviousTxnID: String,
     ///
     pub PreviousTxnLgrSeq: BigDecimal,
+    ///The address of a key pair that can be used to sign transactions for this account instead of the master key.
+    pub RegularKey: Option<Account>,
     ///
     pub Sequence: BigDecimal,
     ///
@@ -176,11 +387,11 @@ pub struct QueuedTransaction {
     ///
     pub auth_change: bool,
     ///
-    pub fee: BigDecimal,
+    pub fee: Drops,
     ///
     pub fee_level: BigDecimal,
     ///
-    pub max_spend_drops: BigDecimal,
+    pub max_spend_drops: Drops,
     ///
     pub seq: BigDecimal,
 }
@@ -214,7 +425,7 @@ pub struct QueueData {
     ///
     pub lowest_sequence: BigDecimal,
     ///
-    pub max_spend_drops_total: BigDecimal,
+    pub max_spend_drops_total: Drops,
     ///
     pub transactions: Vec<QueuedTransaction>,
     ///
@@ -243,6 +454,20 @@ pub enum LedgerIndex {
     },
 }
 
+impl LedgerIndex {
+    /// The ledger as a plain sequence number, if it names one. Shortcuts like "validated"
+    /// give `None`.
+    pub fn index(&self) -> Option<u64> {
+        match self {
+            LedgerIndex::Current {
+                ledger_current_index,
+            } => bigdecimal::ToPrimitive::to_u64(ledger_current_index),
+            LedgerIndex::Number { ledger_index } => ledger_index.as_u64(),
+            LedgerIndex::StrValue { ledger_index } => ledger_index.parse().ok(),
+        }
+    }
+}
+
 ///https://xrpl.org/account_info.html
 #[derive(Deserialize, Debug)]
 pub struct AccountInfo {
@@ -261,6 +486,24 @@ pub struct AccountInfo {
     pub ledger_index: LedgerIndex,
 }
 
+impl AccountInfo {
+    /// The `Sequence` of the next transaction of the account: the one after its queued
+    /// transactions when `queue` was set, otherwise the one of the account itself.
+    pub fn next_sequence(&self) -> Option<u32> {
+        let account_sequence = self
+            .account_data
+            .as_ref()
+            .and_then(|account_data| bigdecimal::ToPrimitive::to_u32(&account_data.Sequence));
+        let after_queued = self
+            .queue_data
+            .as_ref()
+            .and_then(|queue_data| queue_data.highest_sequence.as_ref())
+            .and_then(bigdecimal::ToPrimitive::to_u32)
+            .map(|highest_sequence| highest_sequence + 1);
+        account_sequence.max(after_queued)
+    }
+}
+
 /// See [1]
 /// 1: https://xrpl.org/account_tx.html
 #[derive(Deserialize, Debug)]
@@ -273,10 +516,95 @@ pub struct AccountTx {
     pub ledger_index_max: i64,
     ///
     pub limit: i64,
+    ///Server-defined value indicating the response is paginated. Pass this to the next call to resume where this call left off.
+    pub marker: Option<serde_json::Value>,
     ///
     pub transactions: Vec<AccountTransaction>,
 }
 
+/// See [1]
+/// 1: https://xrpl.org/account_lines.html
+#[derive(Deserialize, Debug)]
+pub struct AccountLines {
+    ///Unique Address of the account this request corresponds to. This is the "perspective account" for purpose of the trust lines.
+    pub account: Account,
+    ///
+    pub lines: Vec<TrustLine>,
+    ///
+    pub ledger_hash: Option<String>,
+    ///
+    #[serde(flatten)]
+    pub ledger_index: LedgerIndex,
+    ///Server-defined value indicating the response is paginated. Pass this to the next call to resume where this call left off.
+    pub marker: Option<serde_json::Value>,
+    ///
+    pub validated: Option<bool>,
+}
+
+/// See [1]
+/// 1: https://xrpl.org/account_objects.html
+#[derive(Deserialize, Debug)]
+pub struct AccountObjects {
+    ///
+    pub account: Account,
+    ///The objects owned by the account, each one tagged by its `LedgerEntryType`
+    pub account_objects: Vec<LedgerObject>,
+    ///
+    pub ledger_hash: Option<String>,
+    ///
+    #[serde(flatten)]
+    pub ledger_index: LedgerIndex,
+    ///
+    pub limit: Option<u64>,
+    ///Server-defined value indicating the response is paginated. Pass this to the next call to resume where this call left off.
+    pub marker: Option<serde_json::Value>,
+    ///
+    pub validated: Option<bool>,
+}
+
+/// A trust line seen from the perspective of the requested account. The `currency` and the
+/// amounts carry the same meaning as in `Balance::Other`, with the peer `account` as the issuer.
+#[derive(Deserialize, Debug)]
+pub struct TrustLine {
+    ///The unique Address of the counterparty to this trust line.
+    pub account: Account,
+    ///Representation of the numeric balance currently held against this line. A positive balance means that the perspective account holds value; a negative balance means that the perspective account owes value.
+    pub balance: IssuedValue,
+    ///The currency code
+    pub currency: String,
+    ///The maximum amount of the given currency that this account is willing to owe the peer account
+    pub limit: IssuedValue,
+    ///The maximum amount of currency that the counterparty account is willing to owe the perspective account
+    pub limit_peer: IssuedValue,
+    ///Rate at which the account values incoming balances on this trust line, as a ratio of this value per 1 billion units. The value 0 is shorthand for treating balances at face value.
+    pub quality_in: u64,
+    ///Rate at which the account values outgoing balances on this trust line, as a ratio of this value per 1 billion units.
+    pub quality_out: u64,
+    ///If true, this account has enabled the No Ripple flag for this line.
+    pub no_ripple: Option<bool>,
+    ///If true, the peer account has enabled the No Ripple flag.
+    pub no_ripple_peer: Option<bool>,
+    ///If true, this account has authorized this trust line.
+    pub authorized: Option<bool>,
+    ///If true, the peer account has authorized this trust line.
+    pub peer_authorized: Option<bool>,
+    ///If true, this account has frozen this trust line.
+    pub freeze: Option<bool>,
+    ///If true, the peer account has frozen this trust line.
+    pub freeze_peer: Option<bool>,
+}
+
+impl TrustLine {
+    /// The balance of the line as an issued currency amount, issued by the peer account.
+    pub fn as_balance(&self) -> Balance {
+        Balance::Other {
+            currency: self.currency.clone(),
+            issuer: self.account.0.clone(),
+            value: self.balance,
+        }
+    }
+}
+
 ///Some fields may be omitted because the values are calculated "lazily" by the queuing mechanism. [1]
 /// 1: https://xrpl.org/account_info.html
 #[derive(Deserialize, Debug)]
@@ -288,29 +616,35 @@ pub struct LaziedQueueData {
     ///
     pub lowest_sequence: Option<BigDecimal>,
     ///
-    pub max_spend_drops_total: Option<BigDecimal>,
+    pub max_spend_drops_total: Option<Drops>,
     ///
     pub transactions: Option<Vec<QueuedTransaction>>,
     ///
     pub txn_count: Option<BigDecimal>,
 }
 
-///
-#[derive(Deserialize, Debug)]
+///A step of a payment path: an account to ripple through, or a currency to convert to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PathInfo {
     ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>,
     ///
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub issuer: Option<String>,
+    ///Which of the fields the step has, added by rippled.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub currency_type: Option<BigDecimal>,
     ///
-    #[serde(rename = "type")]
-    pub currency_type: BigDecimal,
-    ///
-    pub type_hex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_hex: Option<String>,
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct FieldInfo {
     ///
     pub Account: Option<String>,
@@ -325,7 +659,7 @@ pub struct FieldInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PreviousFieldInfo {
     ///
     pub Balance: Option<Balance>,
@@ -334,7 +668,7 @@ pub struct PreviousFieldInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ModifiedNodeInfo {
     ///
     pub FinalFields: Option<FieldInfo>,
@@ -351,7 +685,7 @@ pub struct ModifiedNodeInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct CreatedNodeInfo {
     ///
     pub LedgerEntryType: String,
@@ -362,7 +696,7 @@ pub struct CreatedNodeInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DeletedNodeInfo {
     ///
     pub LedgerEntryType: String,
@@ -373,7 +707,7 @@ pub struct DeletedNodeInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AffectedNodeInfo {
     ///
     pub ModifiedNode: Option<ModifiedNodeInfo>,
@@ -384,7 +718,7 @@ pub struct AffectedNodeInfo {
 }
 
 ///
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct MetaTxInfo {
     ///
     pub AffectedNodes: Vec<AffectedNodeInfo>,
@@ -394,31 +728,12 @@ pub struct MetaTxInfo {
     pub TransactionResult: String,
 }
 
-///
-#[derive(Deserialize, Debug)]
+/// A transaction as the ledger holds it, with its hash and, in a ledger, its metadata.
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TransactionInfo {
-    ///
-    pub Account: String,
-    ///
-    pub Amount: Option<Balance>,
-    ///
-    pub Destination: Option<String>,
-    ///
-    pub Fee: BigDecimal,
-    ///
-    pub Flags: Option<isize>,
-    ///
-    pub Paths: Option<Vec<Vec<PathInfo>>>,
-    ///
-    pub SendMax: Option<Balance>,
-    ///
-    pub Sequence: BigDecimal,
-    ///
-    pub SigningPubKey: String,
-    ///
-    pub TransactionType: String,
-    ///
-    pub TxnSignature: Option<String>,
+    ///The transaction, with the fields of its type.
+    #[serde(flatten)]
+    pub transaction: Transaction,
     ///
     pub hash: String,
     ///
@@ -427,6 +742,101 @@ pub struct TransactionInfo {
     pub validated: Option<bool>, //option of a bool???
 }
 
+impl MetaTxInfo {
+    /// The metadata of a binary `meta`, in hex, as `tx` gives it with `binary` set.
+    pub fn from_meta_blob(meta_blob: &str) -> Result<Self> {
+        let bytes = hex::decode(meta_blob)?;
+        from_binary(&bytes)
+    }
+
+    /// The binary form of the metadata, in hex. Only the fields this struct keeps make it in.
+    pub fn to_meta_blob(&self) -> Result<String> {
+        to_binary(self).map(hex::encode_upper)
+    }
+}
+
+impl TransactionInfo {
+    /// The transaction of a binary `tx_blob`, in hex, with the `hash` it has as a signed
+    /// transaction.
+    pub fn from_tx_blob(tx_blob: &str) -> Result<Self> {
+        let bytes = hex::decode(tx_blob)?;
+        let mut transaction: serde_json::Value = from_binary(&bytes)?;
+        transaction["hash"] = transaction_hash(&bytes).into();
+        Ok(serde_json::from_value(transaction)?)
+    }
+
+    /// The `tx_blob` of the transaction, in hex. Only the fields of its type make it in, all
+    /// of them for a type this crate does not know.
+    pub fn to_tx_blob(&self) -> Result<String> {
+        to_binary(self).map(hex::encode_upper)
+    }
+
+    /// The account whose key signed the transaction, to compare with `Account` and its
+    /// regular key. `None` for multi-signed transactions, which have no `SigningPubKey`.
+    pub fn signing_account(&self) -> Option<Account> {
+        self.transaction
+            .common()?
+            .SigningPubKey
+            .as_ref()?
+            .parse::<PublicKey>()
+            .ok()
+            .map(|public_key| public_key.account())
+    }
+}
+
+/// The answer of `tx` with `binary` set, with its `tx` and `meta` blobs decoded to the JSON
+/// rippled would have answered with.
+fn decode_binary_tx(response: &serde_json::Value) -> Result<serde_json::Value, serde_json::Error> {
+    let decode = |blob: &serde_json::Value| -> Result<serde_json::Value, serde_json::Error> {
+        let blob = blob
+            .as_str()
+            .ok_or_else(|| serde::de::Error::custom("expected a hex blob"))?;
+        let bytes = hex::decode(blob).map_err(serde::de::Error::custom)?;
+        from_binary(&bytes).map_err(serde::de::Error::custom)
+    };
+    let mut decoded = response.clone();
+    if let Some(fields) = decoded.as_object_mut() {
+        if let Some(tx_blob) = fields.remove("tx") {
+            if let serde_json::Value::Object(transaction) = decode(&tx_blob)? {
+                fields.extend(transaction);
+            }
+        }
+        if let Some(meta_blob) = fields.get("meta").filter(|meta| meta.is_string()) {
+            let meta = decode(meta_blob)?;
+            fields.insert("meta".into(), meta);
+        }
+    }
+    Ok(decoded)
+}
+
+/// A single transaction as returned by `tx`, See [1]
+/// 1: https://xrpl.org/tx.html
+#[derive(Deserialize, Debug)]
+pub struct Tx {
+    ///
+    #[serde(flatten)]
+    pub transaction: TransactionInfo,
+    ///Transaction metadata, which describes the results of the transaction.
+    pub meta: Option<MetaTxInfo>,
+    ///The ledger index of the ledger that includes this transaction.
+    pub ledger_index: Option<u64>,
+    ///The close time of the ledger that includes this transaction, in seconds since the Ripple Epoch.
+    pub date: Option<u64>,
+}
+
+/// The outcome of looking a transaction up by its hash.
+#[derive(Debug)]
+pub enum TxResult {
+    /// The node knows the transaction
+    Found(Box<Tx>),
+    /// The node does not know the transaction (`txnNotFound`)
+    NotFound {
+        /// True when a `min_ledger`..`max_ledger` range was given and the node holds every
+        /// ledger in it, so the transaction is definitely not in that range.
+        searched_all: bool,
+    },
+}
+
 ///
 #[derive(Deserialize, Debug)]
 pub struct NestedLedgerInfo {
@@ -487,7 +897,8 @@ pub struct LedgerInfo {
 /// them with the batcher than create a new one.
 #[derive(Clone, Debug)]
 pub struct XRPClient {
-    request_batcher: ReqBatcher,
+    transport: Arc<dyn Transport>,
+    retry_policy: Option<RetryPolicy>,
 }
 impl XRPClient {
     /// Create a client needs to know where to point, like the url, with  the passwords and
@@ -501,51 +912,353 @@ impl XRPClient {
         rps: f64,
         max_batch_size: usize,
     ) -> Result<Self> {
-        Ok(XRPClient {
-            request_batcher: ReqBatcher::new(ClientOptions {
-                uri,
-                batching: max_batch_size,
-                rps: RPS::new(rps)?,
-                concurrent: max_concurrency,
-                client_auth: user.map(|user| ClientAuth {
-                    user,
-                    password: pass,
-                }),
-            }),
-        })
+        Ok(Self::with_transport(ReqBatcherTransport::connect(
+            uri,
+            user,
+            pass,
+            max_concurrency,
+            rps,
+            max_batch_size,
+        )?))
+    }
+    /// Create a client that sends its requests through any transport, like the
+    /// [`MockTransport`] for tests or a [`XRPWebSocketClient`].
+    pub fn with_transport<T: Transport + 'static>(transport: T) -> Self {
+        Self::from_transport(Arc::new(transport))
+    }
+    /// Same as [`XRPClient::with_transport`], for a transport that is shared with someone
+    /// else, like a [`MockTransport`] a test wants to look at afterwards.
+    pub fn from_transport(transport: Arc<dyn Transport>) -> Self {
+        XRPClient {
+            transport,
+            retry_policy: None,
+        }
+    }
+    /// Retries the requests that only read, like `account_info`, `account_tx` or `ledger`,
+    /// when they fail in a way the policy deems transient. Without a policy nothing is
+    /// retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+    /// A request that only reads, so it is retried by the retry policy.
+    async fn call_idempotent<P: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<T, XrpRpcError> {
+        match &self.retry_policy {
+            Some(retry_policy) => retry_policy.run(|| self.call(method, params)).await,
+            None => self.call(method, params).await,
+        }
+    }
+    async fn call<P: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<T, XrpRpcError> {
+        let response = self
+            .transport
+            .request(
+                method,
+                serde_json::to_value(params).map_err(XrpRpcError::Encode)?,
+            )
+            .await?;
+        if let Some(error) = XrpRpcError::from_response(&response) {
+            return Err(error);
+        }
+        serde_json::from_value(response.clone())
+            .map_err(|error| XrpRpcError::decode(error, &response))
     }
     /// Account info is good for getting the balance. See [1] for the source of the
     /// documentation
     ///
     /// 1:https://xrpl.org/account_info.html
-    pub async fn account_info(&mut self, params: &AccountInfoParams<'_>) -> Result<AccountInfo> {
-        self.request_batcher
-            .request(
-                "account_info".to_string(),
-                vec![serde_json::to_value(params)?],
-            )
-            .await
+    pub async fn account_info(
+        &self,
+        params: &AccountInfoParams<'_>,
+    ) -> Result<AccountInfo, XrpRpcError> {
+        self.call_idempotent("account_info", params).await
     }
     /// Account retrieves the transaction fot the given account. See [1] for the source
     /// of the documentation.
     ///
     /// 1: https://xrpl.org/account_tx.html#main_content_body
-    pub async fn account_tx(&mut self, params: &AccountTxParams<'_, '_>) -> Result<AccountTx> {
-        self.request_batcher
-            .request(
-                "account_tx".to_string(),
-                vec![serde_json::to_value(params)?],
-            )
-            .await
+    pub async fn account_tx(
+        &self,
+        params: &AccountTxParams<'_, '_>,
+    ) -> Result<AccountTx, XrpRpcError> {
+        self.call_idempotent("account_tx", params).await
+    }
+    /// Streams every transaction of an account, one at a time, following the `marker` of
+    /// each page into the next one. The `forward`, `limit` and ledger bounds of the params
+    /// are kept for every page, and any `marker` in them is where the stream starts. The
+    /// stream is not `Unpin`, so pin it before polling.
+    ///
+    /// 1: https://xrpl.org/markers-and-pagination.html
+    pub fn account_tx_stream<'a>(
+        &self,
+        params: AccountTxParams<'a, 'a>,
+    ) -> impl Stream<Item = Result<AccountTransaction, XrpRpcError>> + 'a {
+        stream::try_unfold(
+            (self.clone(), params, VecDeque::new(), false),
+            |(client, mut params, mut page, mut done)| async move {
+                loop {
+                    if let Some(transaction) = page.pop_front() {
+                        return Ok(Some((transaction, (client, params, page, done))));
+                    }
+                    if done {
+                        return Ok(None);
+                    }
+                    let response = client.account_tx(&params).await?;
+                    page.extend(response.transactions);
+                    params.marker = response.marker;
+                    done = params.marker.is_none();
+                }
+            },
+        )
+    }
+    /// Account lines returns the trust lines of an account, which is where any issued
+    /// currency it holds or owes lives. See [1] for the source of the documentation.
+    ///
+    /// 1: https://xrpl.org/account_lines.html
+    pub async fn account_lines(
+        &self,
+        params: &AccountLinesParams<'_, '_>,
+    ) -> Result<AccountLines, XrpRpcError> {
+        self.call_idempotent("account_lines", params).await
+    }
+    /// Account objects returns the ledger objects owned by an account, which are the
+    /// things that count towards its owner reserve. See [1] for the source of the
+    /// documentation.
+    ///
+    /// 1: https://xrpl.org/account_objects.html
+    pub async fn account_objects(
+        &self,
+        params: &AccountObjectsParams<'_, '_>,
+    ) -> Result<AccountObjects, XrpRpcError> {
+        self.call_idempotent("account_objects", params).await
+    }
+    /// Tx looks a single transaction up by its hash. A `txnNotFound` answer is not an error
+    /// but a [`TxResult::NotFound`], which tells if the whole `min_ledger`..`max_ledger`
+    /// range was searched. With `binary` set the node answers with hex blobs, which are
+    /// decoded here into the same [`Tx`]. See [1] for the source of the documentation.
+    ///
+    /// 1: https://xrpl.org/tx.html
+    pub async fn tx(
+        &self,
+        hash: &str,
+        binary: bool,
+        min_ledger: Option<u32>,
+        max_ledger: Option<u32>,
+    ) -> Result<TxResult, XrpRpcError> {
+        let params = TxParams {
+            transaction: hash,
+            binary,
+            min_ledger,
+            max_ledger,
+        };
+        match self.call_idempotent("tx", &params).await {
+            Ok(response) => {
+                let decoded = if binary {
+                    decode_binary_tx(&response)
+                        .map_err(|error| XrpRpcError::decode(error, &response))?
+                } else {
+                    response.clone()
+                };
+                let tx = serde_json::from_value(decoded)
+                    .map_err(|error| XrpRpcError::decode(error, &response))?;
+                Ok(TxResult::Found(Box::new(tx)))
+            }
+            Err(XrpRpcError::Rippled {
+                code: RippledErrorCode::TxnNotFound,
+                response,
+                ..
+            }) => Ok(TxResult::NotFound {
+                searched_all: response
+                    .get("searched_all")
+                    .and_then(|searched_all| searched_all.as_bool())
+                    .unwrap_or(false),
+            }),
+            Err(error) => Err(error),
+        }
     }
-    /// This is the meat of the scanner, it gets the information of a block. See [1]
+    /// This is the meat of the scanner (see [`LedgerScanner`]), it gets the information of a block. See [1]
     /// for the source of the documenation. Can get the ledger height, good for knowing
     /// the state of the scanner.
     ///
     /// 1: https://xrpl.org/ledger.html
-    pub async fn ledger(&mut self, params: &LedgerInfoParams) -> Result<LedgerInfo> {
-        self.request_batcher
-            .request("ledger".to_string(), vec![serde_json::to_value(params)?])
+    pub async fn ledger(&self, params: &LedgerInfoParams) -> Result<LedgerInfo, XrpRpcError> {
+        self.call_idempotent("ledger", params).await
+    }
+    /// Fee tells the current transaction cost, and how busy the open ledger and the queue
+    /// are, see [`FeeEstimator`] to turn it into the fee to pay. See [1] for the source of
+    /// the documentation.
+    ///
+    /// 1: https://xrpl.org/fee.html
+    pub async fn fee(&self) -> Result<Fee, XrpRpcError> {
+        self.call_idempotent("fee", &serde_json::json!({})).await
+    }
+    /// The SignerList of an account, `None` when it has none. Check the signers of a
+    /// multi-signed transaction against it with [`SignerListObject::check_quorum`].
+    pub async fn signer_list(
+        &self,
+        account: &Account,
+    ) -> Result<Option<SignerListObject>, XrpRpcError> {
+        let params = AccountObjectsParams {
+            account,
+            object_type: Some(AccountObjectType::SignerList),
+            deletion_blockers_only: None,
+            ledger_hash: None,
+            ledger_index: Some(LedgerIndex::StrValue {
+                ledger_index: "validated".into(),
+            }),
+            limit: None,
+            marker: None,
+        };
+        let objects = self.account_objects(&params).await?;
+        Ok(objects
+            .account_objects
+            .into_iter()
+            .find_map(|object| match object {
+                LedgerObject::SignerList(signer_list) => Some(signer_list),
+                _ => None,
+            }))
+    }
+    /// Submit multisigned sends a transaction put together with [`combine_signers`]. It is
+    /// not retried, the answer only says what the node thinks of the transaction for now.
+    /// See [1] for the source of the documentation.
+    ///
+    /// 1: https://xrpl.org/submit_multisigned.html
+    pub async fn submit_multisigned(
+        &self,
+        transaction: &MultisignedTransaction,
+    ) -> Result<Submit, XrpRpcError> {
+        self.call(
+            "submit_multisigned",
+            &serde_json::json!({ "tx_json": transaction.tx_json }),
+        )
+        .await
+    }
+    /// Submit sends a signed transaction, like one from [`sign_transaction`]. It is not
+    /// retried, and the answer is only preliminary: see [`ReliableSubmitter`] to follow the
+    /// transaction until its outcome is final. See [1] for the source of the documentation.
+    ///
+    /// 1: https://xrpl.org/submit.html
+    pub async fn submit(&self, tx_blob: &str) -> Result<Submit, XrpRpcError> {
+        self.call("submit", &serde_json::json!({ "tx_blob": tx_blob }))
+            .await
+    }
+    /// Autofill completes a transaction before signing, filling in the fields it does not
+    /// set: the `Sequence` that follows the account's queued transactions, the `Fee` a
+    /// [`FeeEstimator`] recommends for the open ledger with `urgency`, capped at `max_fee`,
+    /// and a `LastLedgerSequence` `ledger_offset` ledgers after the latest validated one, see
+    /// [`ReliableSubmitter::DEFAULT_LEDGER_OFFSET`]. Only what is missing is asked for.
+    pub async fn autofill<T: Serialize + DeserializeOwned>(
+        &self,
+        transaction: &T,
+        urgency: FeeUrgency,
+        max_fee: Option<Drops>,
+        ledger_offset: u32,
+    ) -> Result<T, XrpRpcError> {
+        let mut transaction = serde_json::to_value(transaction).map_err(XrpRpcError::Encode)?;
+        let is_missing = |transaction: &serde_json::Value, field: &str| {
+            matches!(transaction.get(field), None | Some(serde_json::Value::Null))
+        };
+        if is_missing(&transaction, "Sequence") || is_missing(&transaction, "Fee") {
+            let account: Account = transaction["Account"]
+                .as_str()
+                .ok_or_else(|| XrpRpcError::InvalidTransaction("No Account".into()))?
+                .parse()
+                .map_err(|error| {
+                    XrpRpcError::InvalidTransaction(format!("The Account is not valid: {}", error))
+                })?;
+            // Queued transactions are only in the current ledger
+            let info = self
+                .account_info(&AccountInfoParams {
+                    account: &account,
+                    strict: true,
+                    ledger_index: LedgerIndex::StrValue {
+                        ledger_index: "current".into(),
+                    },
+                    queue: true,
+                })
+                .await?;
+            if is_missing(&transaction, "Sequence") {
+                let sequence = info.next_sequence().ok_or_else(|| {
+                    XrpRpcError::InvalidTransaction(format!("No Sequence for account {}", account))
+                })?;
+                transaction["Sequence"] = sequence.into();
+            }
+            if is_missing(&transaction, "Fee") {
+                let mut estimator = FeeEstimator::new(self.fee().await?);
+                if let Some(queue_data) = &info.queue_data {
+                    estimator = estimator.with_queue(queue_data);
+                }
+                if let Some(max_fee) = max_fee {
+                    estimator = estimator.with_max_fee(max_fee);
+                }
+                transaction["Fee"] = serde_json::to_value(estimator.recommend(urgency))
+                    .map_err(XrpRpcError::Encode)?;
+            }
+        }
+        if is_missing(&transaction, "LastLedgerSequence") {
+            let validated_index = self.validated_ledger_index().await?;
+            let last_ledger_sequence = u32::try_from(validated_index)
+                .ok()
+                .and_then(|validated_index| validated_index.checked_add(ledger_offset))
+                .ok_or_else(|| {
+                    XrpRpcError::InvalidTransaction(format!(
+                        "No LastLedgerSequence {} ledgers after ledger {}",
+                        ledger_offset, validated_index
+                    ))
+                })?;
+            transaction["LastLedgerSequence"] = last_ledger_sequence.into();
+        }
+        serde_json::from_value(transaction.clone())
+            .map_err(|error| XrpRpcError::decode(error, &transaction))
+    }
+    /// The index of the latest validated ledger.
+    pub(crate) async fn validated_ledger_index(&self) -> Result<u64, XrpRpcError> {
+        let response = self
+            .ledger(&LedgerInfoParams {
+                ledger_hash: None,
+                ledger_index: Some(LedgerIndex::StrValue {
+                    ledger_index: "validated".into(),
+                }),
+                full: None,
+                accounts: None,
+                transactions: None,
+                expand: None,
+                owner_funds: None,
+                binary: None,
+                queue: None,
+            })
+            .await?;
+        response
+            .ledger_index
+            .index()
+            .ok_or_else(|| XrpRpcError::Decode {
+                source: serde::de::Error::custom("No index for the validated ledger"),
+                body: format!("{:?}", response.ledger_index),
+            })
+    }
+    /// Server info tells how far along the node is: its state, the ledgers it holds, the
+    /// last validated ledger with the reserves and base fee, and its load. See [1] for the
+    /// source of the documentation.
+    ///
+    /// 1: https://xrpl.org/server_info.html
+    pub async fn server_info(&self) -> Result<ServerInfo, XrpRpcError> {
+        self.call_idempotent("server_info", &serde_json::json!({}))
+            .await
+    }
+    /// Server state is the same as server info, with the fees and reserves in drops and the
+    /// load factors unscaled, which is what to use in calculations. See [1] for the source
+    /// of the documentation.
+    ///
+    /// 1: https://xrpl.org/server_state.html
+    pub async fn server_state(&self) -> Result<ServerState, XrpRpcError> {
+        self.call_idempotent("server_state", &serde_json::json!({}))
             .await
     }
 }
@@ -595,3 +1308,252 @@ fn json_ledger_test() {
     )
     .unwrap();
 }
+
+#[test]
+fn json_account_lines_test() {
+    let lines: AccountLines = serde_json::from_str(
+        r#"{
+    "account": "rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn",
+    "ledger_current_index": 56867201,
+    "lines": [
+      {
+        "account": "r3vi7mWxru9rJCxETCyA1CHvzL96eZWx5z",
+        "balance": "0",
+        "currency": "ASP",
+        "limit": "0",
+        "limit_peer": "10",
+        "quality_in": 0,
+        "quality_out": 0
+      },
+      {
+        "account": "rMwjYedjc7qqtKYVLiAccJSmCwih4LnE2q",
+        "balance": "-5",
+        "currency": "USD",
+        "limit": "0",
+        "limit_peer": "100",
+        "no_ripple": true,
+        "quality_in": 0,
+        "quality_out": 0
+      }
+    ],
+    "status": "success",
+    "validated": false
+  }"#,
+    )
+    .unwrap();
+    assert_eq!(lines.lines.len(), 2);
+    match lines.lines[1].as_balance() {
+        Balance::Other {
+            currency,
+            issuer,
+            value,
+        } => {
+            assert_eq!(currency, "USD");
+            assert_eq!(issuer, "rMwjYedjc7qqtKYVLiAccJSmCwih4LnE2q");
+            assert_eq!(value, IssuedValue::new(-5, 0).unwrap());
+        }
+        Balance::XRP(_) => panic!("Trust line balance should be an issued currency"),
+    }
+}
+
+#[test]
+fn json_account_objects_test() {
+    let objects: AccountObjects = serde_json::from_str(
+        r#"{
+    "account": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+    "account_objects": [
+      {
+        "Balance": {
+          "currency": "ASP",
+          "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji",
+          "value": "0"
+        },
+        "Flags": 65536,
+        "HighLimit": {
+          "currency": "ASP",
+          "issuer": "r3vi7mWxru9rJCxETCyA1CHvzL96eZWx5z",
+          "value": "0"
+        },
+        "HighNode": "0000000000000000",
+        "LedgerEntryType": "RippleState",
+        "LowLimit": {
+          "currency": "ASP",
+          "issuer": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+          "value": "10"
+        },
+        "LowNode": "0000000000000000",
+        "PreviousTxnID": "BF7555B0F018E3C5E2A3FF9437A1A5092F32903BE246202F988181B9CED0D862",
+        "PreviousTxnLgrSeq": 1438879,
+        "index": "2243B0B630EA6F7330B654EFA53E27A7609D9484E535AB11B7F946DF3D247CE9"
+      },
+      {
+        "Flags": 0,
+        "LedgerEntryType": "SignerList",
+        "OwnerNode": "0000000000000000",
+        "PreviousTxnID": "5904C0DC72C58A83AEFED2FFC5386356AA83FCA6A88C89D00646E51E687CDBE4",
+        "PreviousTxnLgrSeq": 16061435,
+        "SignerEntries": [
+          {
+            "SignerEntry": {
+              "Account": "rsA2LpzuawewSBQXkiju3YQTMzW13pAAdW",
+              "SignerWeight": 2
+            }
+          },
+          {
+            "SignerEntry": {
+              "Account": "raKEEVSGnKSD9Zyvxu4z6Pqpm4ABH8FS6n",
+              "SignerWeight": 1
+            }
+          }
+        ],
+        "SignerListID": 0,
+        "SignerQuorum": 3,
+        "index": "A9C28A28B85CD533217F5C0A0C7767666B093FA58A0F2D80026FCC4CD932DDC7"
+      },
+      {
+        "Account": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+        "BookDirectory": "F0B9A528CE25FE77C51C38040A7FEC016C2C841E74C1418D5B038D7EA4C68000",
+        "BookNode": "0000000000000000",
+        "Flags": 131072,
+        "LedgerEntryType": "Offer",
+        "OwnerNode": "0000000000000000",
+        "PreviousTxnID": "9BB337CC8B34DC8D1A3FFF468556C8BA70977C37F7436439D8DA19610F214AD1",
+        "PreviousTxnLgrSeq": 8135544,
+        "Sequence": 1405,
+        "TakerGets": {
+          "currency": "CNY",
+          "issuer": "rKiCet8SdvWxPXnAgYarFUXMh1zCPz432Y",
+          "value": "1"
+        },
+        "TakerPays": "1000000",
+        "index": "C8EB5C0D56DE2CA9D4F2F0F02C5D2D0D4F59B4E3C2B1F3AD1F0D6C20A5E90C1A"
+      },
+      {
+        "Amount": "1000000",
+        "Flags": 1,
+        "LedgerEntryType": "NFTokenOffer",
+        "NFTokenID": "00081B5825A08C22787716FA031B432EBBC1B101BB54875F0002D2A400000000",
+        "NFTokenOfferNode": "0",
+        "Owner": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+        "OwnerNode": "17",
+        "PreviousTxnID": "BFA9BE27383FA315651E26FDE1FA30815C5A5D0544EE10EC33D3E92532993769",
+        "PreviousTxnLgrSeq": 75443565,
+        "index": "AEBABA4FAC212BF28E0F9A9C7F1D6B5A2B95E8D2F4C1D8E03C3A5F7B8E9D0C1A"
+      }
+    ],
+    "ledger_hash": "053DF17D2289D1C4971C22F235BC1FCA7D4B3AE966F842E5819D0749E0B8ECD3",
+    "ledger_index": 14378733,
+    "limit": 10,
+    "validated": true
+  }"#,
+    )
+    .unwrap();
+    assert_eq!(objects.account_objects.len(), 4);
+    match &objects.account_objects[1] {
+        LedgerObject::SignerList(list) => {
+            assert_eq!(list.SignerQuorum, 3);
+            assert_eq!(list.SignerEntries[0].SignerEntry.SignerWeight, 2);
+        }
+        other => panic!("Expecting a SignerList and got {:?}", other),
+    }
+    let offer = &objects.account_objects[3];
+    assert!(matches!(offer, LedgerObject::Unknown(_)));
+    assert_eq!(offer.ledger_entry_type(), "NFTokenOffer");
+    assert_eq!(
+        offer.index(),
+        "AEBABA4FAC212BF28E0F9A9C7F1D6B5A2B95E8D2F4C1D8E03C3A5F7B8E9D0C1A"
+    );
+    // A SignerList that cannot be read is an error, not an unknown object
+    let broken_list = serde_json::json!({
+        "LedgerEntryType": "SignerList",
+        "SignerQuorum": "three",
+    });
+    assert!(serde_json::from_value::<LedgerObject>(broken_list).is_err());
+    assert_eq!(
+        serde_json::to_value(AccountObjectType::PaymentChannel).unwrap(),
+        serde_json::json!("payment_channel")
+    );
+    assert_eq!(
+        serde_json::to_value(AccountObjectType::NftOffer).unwrap(),
+        serde_json::json!("nft_offer")
+    );
+}
+
+#[test]
+fn json_tx_test() {
+    let tx: Tx = serde_json::from_str(
+        r#"{
+    "Account": "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh",
+    "Amount": "1000000000",
+    "Destination": "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf",
+    "Fee": "10",
+    "Flags": 2147483648,
+    "Sequence": 1,
+    "SigningPubKey": "0330E7FC9D56BB25D6893BA3F317AE5BCF33B3291BD63DB32654A313222F7FD020",
+    "TransactionType": "Payment",
+    "TxnSignature": "3045022100AB3E0B0E1A8B4A7D3A8AB5B4CE66F9D2B8E3F2C8C7C21A5E2B94B1F6F6E09A702205E9CE4F0A5F1FF4BD3F0C39F4F6F8C71BFBB9E7E1E6B9D8D0D1A5D8C0B4E3A2C",
+    "date": 647619171,
+    "hash": "0F1F22E6F5A2A7E4B6C0F9B1F7D6F9A7A5C1E4C3B8F2D0C9A3E6E1B2D4C7F8A9",
+    "inLedger": 54924361,
+    "ledger_index": 54924361,
+    "meta": {
+      "AffectedNodes": [
+        {
+          "ModifiedNode": {
+            "FinalFields": {
+              "Account": "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh",
+              "Balance": "99999998999999990",
+              "Flags": 0,
+              "OwnerCount": 0,
+              "Sequence": 2
+            },
+            "LedgerEntryType": "AccountRoot",
+            "LedgerIndex": "2B6AC232AA4C4BE41BF49D2459FA4A0347E1B543A4C92FCEE0821C0201E2E9A8",
+            "PreviousFields": {
+              "Balance": "100000000000000000",
+              "Sequence": 1
+            }
+          }
+        }
+      ],
+      "TransactionIndex": 0,
+      "TransactionResult": "tesSUCCESS"
+    },
+    "validated": true
+  }"#,
+    )
+    .unwrap();
+    match &tx.transaction.transaction {
+        Transaction::Payment(payment) => {
+            assert_eq!(
+                payment.Destination.to_string(),
+                "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf"
+            );
+            assert_eq!(payment.common.Sequence, Some(1));
+        }
+        other => panic!("Expecting a Payment and got {:?}", other),
+    }
+    assert_eq!(tx.transaction.validated, Some(true));
+    assert_eq!(tx.ledger_index, Some(54924361));
+    let meta = tx.meta.unwrap();
+    assert_eq!(meta.TransactionResult, "tesSUCCESS");
+    let meta = MetaTxInfo::from_meta_blob(&meta.to_meta_blob().unwrap()).unwrap();
+    assert_eq!(meta.TransactionResult, "tesSUCCESS");
+    let node = meta.AffectedNodes[0].ModifiedNode.as_ref().unwrap();
+    assert_eq!(node.LedgerEntryType, "AccountRoot");
+    match &node.PreviousFields.as_ref().unwrap().Balance {
+        Some(Balance::XRP(drops)) => assert_eq!(drops.whole_xrp(), 100_000_000_000),
+        other => panic!("Expecting an XRP balance and got {:?}", other),
+    }
+}
+
+#[test]
+fn ledger_index_test() {
+    let info: LedgerInfo =
+        serde_json::from_reader(std::fs::File::open("ledger.json").unwrap()).unwrap();
+    assert_eq!(info.ledger.unwrap().ledger_index.index(), Some(48293247));
+    let validated = LedgerIndex::StrValue {
+        ledger_index: "validated".into(),
+    };
+    assert_eq!(validated.index(), None);
+}
@@ -0,0 +1,486 @@
+//! The ledger objects an account can own, as returned by `account_objects`. See [1] for
+//! the source of the documentation.
+//!
+//! 1: https://xrpl.org/ledger-object-types.html
+
+use crate::{Balance, Signer};
+use anyhow::anyhow;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Any object that can sit in an account's owner directory, tagged by its `LedgerEntryType`.
+/// The kinds this crate does not know are kept as raw JSON.
+#[derive(Debug)]
+pub enum LedgerObject {
+    ///https://xrpl.org/ripplestate.html
+    RippleState(RippleStateObject),
+    ///https://xrpl.org/offer.html
+    Offer(OfferObject),
+    ///https://xrpl.org/escrow-object.html
+    Escrow(EscrowObject),
+    ///https://xrpl.org/paychannel.html
+    PayChannel(PayChannelObject),
+    ///https://xrpl.org/check.html
+    Check(CheckObject),
+    ///https://xrpl.org/signerlist.html
+    SignerList(SignerListObject),
+    ///https://xrpl.org/ticket.html
+    Ticket(TicketObject),
+    ///https://xrpl.org/depositpreauth-object.html
+    DepositPreauth(DepositPreauthObject),
+    ///https://xrpl.org/nftokenpage.html
+    NFTokenPage(NFTokenPageObject),
+    ///https://xrpl.org/amm.html
+    AMM(AMMObject),
+    /// An object of a kind this crate does not know about, like an NFTokenOffer
+    Unknown(Value),
+}
+
+impl LedgerObject {
+    /// The unique ID of the object in the ledger state.
+    pub fn index(&self) -> &str {
+        match self {
+            LedgerObject::RippleState(o) => &o.index,
+            LedgerObject::Offer(o) => &o.index,
+            LedgerObject::Escrow(o) => &o.index,
+            LedgerObject::PayChannel(o) => &o.index,
+            LedgerObject::Check(o) => &o.index,
+            LedgerObject::SignerList(o) => &o.index,
+            LedgerObject::Ticket(o) => &o.index,
+            LedgerObject::DepositPreauth(o) => &o.index,
+            LedgerObject::NFTokenPage(o) => &o.index,
+            LedgerObject::AMM(o) => &o.index,
+            LedgerObject::Unknown(o) => o["index"].as_str().unwrap_or_default(),
+        }
+    }
+
+    /// The `LedgerEntryType`, like `RippleState`.
+    pub fn ledger_entry_type(&self) -> &str {
+        match self {
+            LedgerObject::RippleState(_) => "RippleState",
+            LedgerObject::Offer(_) => "Offer",
+            LedgerObject::Escrow(_) => "Escrow",
+            LedgerObject::PayChannel(_) => "PayChannel",
+            LedgerObject::Check(_) => "Check",
+            LedgerObject::SignerList(_) => "SignerList",
+            LedgerObject::Ticket(_) => "Ticket",
+            LedgerObject::DepositPreauth(_) => "DepositPreauth",
+            LedgerObject::NFTokenPage(_) => "NFTokenPage",
+            LedgerObject::AMM(_) => "AMM",
+            LedgerObject::Unknown(o) => o["LedgerEntryType"].as_str().unwrap_or_default(),
+        }
+    }
+}
+
+// An object of a type the crate does not know does not fail the whole page of `account_objects`
+impl<'de> Deserialize<'de> for LedgerObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn typed<T: DeserializeOwned, E: Error>(
+            object: Value,
+            variant: fn(T) -> LedgerObject,
+        ) -> Result<LedgerObject, E> {
+            serde_json::from_value(object)
+                .map(variant)
+                .map_err(E::custom)
+        }
+        let object = Value::deserialize(deserializer)?;
+        match object["LedgerEntryType"].as_str() {
+            Some("RippleState") => typed(object, LedgerObject::RippleState),
+            Some("Offer") => typed(object, LedgerObject::Offer),
+            Some("Escrow") => typed(object, LedgerObject::Escrow),
+            Some("PayChannel") => typed(object, LedgerObject::PayChannel),
+            Some("Check") => typed(object, LedgerObject::Check),
+            Some("SignerList") => typed(object, LedgerObject::SignerList),
+            Some("Ticket") => typed(object, LedgerObject::Ticket),
+            Some("DepositPreauth") => typed(object, LedgerObject::DepositPreauth),
+            Some("NFTokenPage") => typed(object, LedgerObject::NFTokenPage),
+            Some("AMM") => typed(object, LedgerObject::AMM),
+            Some(_) => Ok(LedgerObject::Unknown(object)),
+            None => Err(D::Error::missing_field("LedgerEntryType")),
+        }
+    }
+}
+
+///A trust line between two accounts, holding the balance of an issued currency.
+#[derive(Deserialize, Debug)]
+pub struct RippleStateObject {
+    ///The balance of the trust line, from the perspective of the low account. A negative balance indicates that the high account holds tokens issued by the low account.
+    pub Balance: Balance,
+    ///
+    pub Flags: u32,
+    ///The limit that the high account has set on the trust line. The issuer is the address of the high account that set this limit.
+    pub HighLimit: Balance,
+    ///
+    pub HighNode: String,
+    ///
+    pub HighQualityIn: Option<u32>,
+    ///
+    pub HighQualityOut: Option<u32>,
+    ///The limit that the low account has set on the trust line. The issuer is the address of the low account that set this limit.
+    pub LowLimit: Balance,
+    ///
+    pub LowNode: String,
+    ///
+    pub LowQualityIn: Option<u32>,
+    ///
+    pub LowQualityOut: Option<u32>,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///
+    pub index: String,
+}
+
+///An order to make a currency trade in the decentralized exchange.
+#[derive(Deserialize, Debug)]
+pub struct OfferObject {
+    ///
+    pub Account: String,
+    ///
+    pub BookDirectory: String,
+    ///
+    pub BookNode: String,
+    ///
+    pub Expiration: Option<u32>,
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///The Sequence value of the OfferCreate transaction that created this offer.
+    pub Sequence: u32,
+    ///The remaining amount and type of currency being provided by the offer creator.
+    pub TakerGets: Balance,
+    ///The remaining amount and type of currency requested by the offer creator.
+    pub TakerPays: Balance,
+    ///
+    pub index: String,
+}
+
+///XRP held aside until a time or condition is met.
+#[derive(Deserialize, Debug)]
+pub struct EscrowObject {
+    ///The owner (sender) of the escrowed XRP.
+    pub Account: String,
+    ///
+    pub Amount: Balance,
+    ///
+    pub CancelAfter: Option<u32>,
+    ///
+    pub Condition: Option<String>,
+    ///
+    pub Destination: String,
+    ///
+    pub DestinationNode: Option<String>,
+    ///
+    pub DestinationTag: Option<u32>,
+    ///
+    pub FinishAfter: Option<u32>,
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///
+    pub SourceTag: Option<u32>,
+    ///
+    pub index: String,
+}
+
+///A one-way channel for asynchronous XRP payments.
+#[derive(Deserialize, Debug)]
+pub struct PayChannelObject {
+    ///The source address that owns this payment channel.
+    pub Account: String,
+    ///Total XRP that has been allocated to this channel, including the paid out balance.
+    pub Amount: Balance,
+    ///Total XRP already paid out by the channel.
+    pub Balance: Balance,
+    ///
+    pub CancelAfter: Option<u32>,
+    ///
+    pub Destination: String,
+    ///
+    pub DestinationNode: Option<String>,
+    ///
+    pub DestinationTag: Option<u32>,
+    ///
+    pub Expiration: Option<u32>,
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///
+    pub PublicKey: String,
+    ///
+    pub SettleDelay: u32,
+    ///
+    pub SourceTag: Option<u32>,
+    ///
+    pub index: String,
+}
+
+///A deferred payment that the destination can cash.
+#[derive(Deserialize, Debug)]
+pub struct CheckObject {
+    ///The sender of the Check.
+    pub Account: String,
+    ///
+    pub Destination: String,
+    ///
+    pub DestinationNode: Option<String>,
+    ///
+    pub DestinationTag: Option<u32>,
+    ///
+    pub Expiration: Option<u32>,
+    ///
+    pub Flags: u32,
+    ///
+    pub InvoiceID: Option<String>,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///The maximum amount of currency this Check can debit the sender.
+    pub SendMax: Balance,
+    ///
+    pub Sequence: u32,
+    ///
+    pub SourceTag: Option<u32>,
+    ///
+    pub index: String,
+}
+
+///The list of signers that can authorize transactions for the account by multi-signing.
+#[derive(Deserialize, Debug)]
+pub struct SignerListObject {
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///
+    pub SignerEntries: Vec<SignerEntryWrapper>,
+    ///
+    pub SignerListID: u32,
+    ///A target number for signer weights. To produce a valid signature for the owner of this SignerList, the signers must provide valid signatures whose weights sum to this value or more.
+    pub SignerQuorum: u32,
+    ///
+    pub index: String,
+}
+
+impl SignerListObject {
+    /// Checks what rippled checks of the signers of a multi-signed transaction: that they
+    /// are all on the list, and that their weights add up to the quorum.
+    pub fn check_quorum(&self, signers: &[Signer]) -> anyhow::Result<()> {
+        let mut weight = 0;
+        for signer in signers {
+            let account = signer.Account.to_string();
+            let entry = self
+                .SignerEntries
+                .iter()
+                .find(|wrapper| wrapper.SignerEntry.Account == account)
+                .ok_or_else(|| anyhow!("{} is not on the SignerList", account))?;
+            weight += u32::from(entry.SignerEntry.SignerWeight);
+        }
+        if weight < self.SignerQuorum {
+            return Err(anyhow!(
+                "The signers weigh {}, short of the quorum of {}",
+                weight,
+                self.SignerQuorum
+            ));
+        }
+        Ok(())
+    }
+}
+
+///The ledger wraps every entry of a `SignerEntries` array in a `SignerEntry` object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignerEntryWrapper {
+    ///
+    pub SignerEntry: SignerEntry,
+}
+
+///https://xrpl.org/signerlist.html#signer-entry-object
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignerEntry {
+    ///An XRP Ledger address whose signature contributes to the multi-signature.
+    pub Account: String,
+    ///The weight of a signature from this signer.
+    pub SignerWeight: u16,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WalletLocator: Option<String>,
+}
+
+///A sequence number set aside for a future transaction.
+#[derive(Deserialize, Debug)]
+pub struct TicketObject {
+    ///
+    pub Account: String,
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///The Sequence Number this Ticket sets aside.
+    pub TicketSequence: u32,
+    ///
+    pub index: String,
+}
+
+///A preauthorization for another account to send payments to this one.
+#[derive(Deserialize, Debug)]
+pub struct DepositPreauthObject {
+    ///The account that granted the preauthorization.
+    pub Account: String,
+    ///The account that received the preauthorization.
+    pub Authorize: String,
+    ///
+    pub Flags: u32,
+    ///
+    pub OwnerNode: String,
+    ///
+    pub PreviousTxnID: String,
+    ///
+    pub PreviousTxnLgrSeq: u32,
+    ///
+    pub index: String,
+}
+
+///A page of up to 32 NFTokens owned by the same account.
+#[derive(Deserialize, Debug)]
+pub struct NFTokenPageObject {
+    ///
+    pub Flags: Option<u32>,
+    ///
+    pub NextPageMin: Option<String>,
+    ///
+    pub PreviousPageMin: Option<String>,
+    ///
+    pub PreviousTxnID: Option<String>,
+    ///
+    pub PreviousTxnLgrSeq: Option<u32>,
+    ///
+    pub NFTokens: Vec<NFTokenWrapper>,
+    ///
+    pub index: String,
+}
+
+///The ledger wraps every entry of an `NFTokens` array in an `NFToken` object.
+#[derive(Deserialize, Debug)]
+pub struct NFTokenWrapper {
+    ///
+    pub NFToken: NFToken,
+}
+
+///https://xrpl.org/nftoken.html
+#[derive(Deserialize, Debug)]
+pub struct NFToken {
+    ///
+    pub NFTokenID: String,
+    ///
+    pub URI: Option<String>,
+}
+
+///An automated market maker instance, owned by its special AMM account.
+#[derive(Deserialize, Debug)]
+pub struct AMMObject {
+    ///The address of the special account that holds this AMM's assets.
+    pub Account: String,
+    ///
+    pub Asset: Issue,
+    ///
+    pub Asset2: Issue,
+    ///
+    pub AuctionSlot: Option<AuctionSlot>,
+    ///
+    pub Flags: Option<u32>,
+    ///The total outstanding balance of liquidity provider tokens from this AMM instance.
+    pub LPTokenBalance: Balance,
+    ///
+    pub OwnerNode: Option<String>,
+    ///The percentage fee to be charged for trades against this AMM instance, in units of 1/100,000.
+    pub TradingFee: u16,
+    ///
+    pub VoteSlots: Option<Vec<VoteEntryWrapper>>,
+    ///
+    pub index: String,
+}
+
+///A currency without an amount. XRP has no issuer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    ///
+    pub currency: String,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+///https://xrpl.org/amm.html#auction-slot-object
+#[derive(Deserialize, Debug)]
+pub struct AuctionSlot {
+    ///
+    pub Account: String,
+    ///
+    pub AuthAccounts: Option<Vec<AuthAccountWrapper>>,
+    ///
+    pub DiscountedFee: Option<u16>,
+    ///
+    pub Expiration: u32,
+    ///
+    pub Price: Balance,
+}
+
+///The ledger wraps every entry of an `AuthAccounts` array in an `AuthAccount` object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthAccountWrapper {
+    ///
+    pub AuthAccount: AuthAccount,
+}
+
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthAccount {
+    ///
+    pub Account: String,
+}
+
+///The ledger wraps every entry of a `VoteSlots` array in a `VoteEntry` object.
+#[derive(Deserialize, Debug)]
+pub struct VoteEntryWrapper {
+    ///
+    pub VoteEntry: VoteEntry,
+}
+
+///https://xrpl.org/amm.html#vote-slots
+#[derive(Deserialize, Debug)]
+pub struct VoteEntry {
+    ///
+    pub Account: String,
+    ///
+    pub TradingFee: u16,
+    ///
+    pub VoteWeight: u32,
+}
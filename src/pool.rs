@@ -0,0 +1,313 @@
+use crate::{ReqBatcherTransport, RippledErrorCode, ServerInfo, Transport, XrpRpcError};
+use anyhow::anyhow;
+use futures::future::{join_all, BoxFuture};
+use serde_json::{json, Value};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Several rippled or Clio nodes behind one [`Transport`]. Requests go round robin to the
+/// healthy nodes, and a request whose node fails to answer is sent to the next node, so one
+/// node going down is not noticed by the client. Each node keeps its own transport, so the
+/// rps and concurrency limits of an HTTP node are per node.
+///
+/// The health of the nodes comes from `server_info`, through [`NodePool::check_health`] or
+/// [`NodePool::spawn_health_checks`]: a node is taken out of rotation when it is not synced,
+/// is amendment blocked, fails to answer, or lags more than `max_lag` ledgers behind the most
+/// advanced node of the pool.
+///
+/// The checks also learn which ledgers every node holds, from its `complete_ledgers`. A
+/// request for a given ledger, with a number as `ledger_index`, goes to a node that holds it,
+/// then to the full history nodes, see [`NodePool::with_full_history_node`]. A node answering
+/// `lgrNotFound` to such a request is not blamed, the next one is asked.
+#[derive(Debug)]
+pub struct NodePool {
+    nodes: Vec<Node>,
+    next: AtomicUsize,
+    max_lag: u64,
+}
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    transport: Arc<dyn Transport>,
+    full_history: bool,
+    health: Mutex<NodeHealth>,
+}
+
+/// What the pool knows of one of its nodes.
+#[derive(Debug, Clone)]
+pub struct NodeHealth {
+    /// Whether requests are sent to the node
+    pub healthy: bool,
+    /// The last validated ledger of the node, as of the last check
+    pub validated_ledger: Option<u64>,
+    /// Why the node is out of rotation
+    pub last_error: Option<String>,
+    /// When the node was last checked
+    pub last_check: Option<Instant>,
+    /// The ledgers the node holds, empty until the node is checked
+    pub complete_ledgers: Vec<RangeInclusive<u64>>,
+}
+
+impl NodeHealth {
+    /// Whether the node held the ledger as of the last check.
+    pub fn has_ledger(&self, ledger_index: u64) -> bool {
+        self.complete_ledgers
+            .iter()
+            .any(|range| range.contains(&ledger_index))
+    }
+}
+
+impl Default for NodeHealth {
+    /// Nodes are trusted until a check or a request says otherwise.
+    fn default() -> Self {
+        NodeHealth {
+            healthy: true,
+            validated_ledger: None,
+            last_error: None,
+            last_check: None,
+            complete_ledgers: Vec::new(),
+        }
+    }
+}
+
+impl Default for NodePool {
+    fn default() -> Self {
+        NodePool {
+            nodes: Vec::new(),
+            next: AtomicUsize::new(0),
+            max_lag: Self::DEFAULT_MAX_LAG,
+        }
+    }
+}
+
+impl NodePool {
+    /// How many ledgers a node can be behind the others before it is taken out of rotation.
+    pub const DEFAULT_MAX_LAG: u64 = 5;
+
+    /// An empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes how many ledgers a node can be behind the others.
+    pub fn with_max_lag(mut self, max_lag: u64) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+
+    /// Adds a node reached through any transport.
+    pub fn add_node<T: Transport + 'static>(self, name: &str, transport: T) -> Self {
+        self.add_shared_node(name, Arc::new(transport))
+    }
+
+    /// Adds a node whose transport is also used elsewhere.
+    pub fn add_shared_node(mut self, name: &str, transport: Arc<dyn Transport>) -> Self {
+        self.nodes.push(Node {
+            name: name.to_string(),
+            transport,
+            full_history: false,
+            health: Mutex::new(NodeHealth::default()),
+        });
+        self
+    }
+
+    /// Makes the nodes added under `name` the ones to ask for ledgers no other node is known
+    /// to hold, even when their own `complete_ledgers` does not say they have it.
+    pub fn with_full_history_node(mut self, name: &str) -> Self {
+        for node in self.nodes.iter_mut().filter(|node| node.name == name) {
+            node.full_history = true;
+        }
+        self
+    }
+
+    /// Adds an HTTP JSON-RPC node, with its own limits, see [`crate::XRPClient::new`].
+    pub fn add_rpc_node(
+        self,
+        uri: String,
+        user: Option<String>,
+        pass: Option<String>,
+        max_concurrency: usize,
+        rps: f64,
+        max_batch_size: usize,
+    ) -> anyhow::Result<Self> {
+        let name = uri.clone();
+        Ok(self.add_node(
+            &name,
+            ReqBatcherTransport::connect(uri, user, pass, max_concurrency, rps, max_batch_size)?,
+        ))
+    }
+
+    /// The health of every node, by name.
+    pub fn health(&self) -> Vec<(String, NodeHealth)> {
+        self.nodes
+            .iter()
+            .map(|node| (node.name.clone(), node.health.lock().unwrap().clone()))
+            .collect()
+    }
+
+    /// Asks every node for its `server_info` and takes the unhealthy and lagging ones out of
+    /// rotation, putting the recovered ones back.
+    pub async fn check_health(&self) {
+        let checks = join_all(self.nodes.iter().map(|node| async move {
+            let info = node.transport.request("server_info", json!({})).await;
+            let info = info.and_then(|response| {
+                if let Some(error) = XrpRpcError::from_response(&response) {
+                    return Err(error);
+                }
+                serde_json::from_value::<ServerInfo>(response.clone())
+                    .map_err(|error| XrpRpcError::decode(error, &response))
+            });
+            match info {
+                Ok(ServerInfo { info }) => {
+                    let state_error = match info.server_state {
+                        Some(state) if !state.is_synced() => {
+                            Some(format!("server state is {:?}", state))
+                        }
+                        _ => None,
+                    };
+                    let blocked_error = if info.amendment_blocked.unwrap_or(false) {
+                        Some("amendment blocked".to_string())
+                    } else {
+                        None
+                    };
+                    (
+                        info.validated_ledger.as_ref().map(|ledger| ledger.seq),
+                        info.complete_ledger_ranges(),
+                        state_error.or(blocked_error),
+                    )
+                }
+                Err(error) => (None, Vec::new(), Some(error.to_string())),
+            }
+        }))
+        .await;
+        let tip = checks
+            .iter()
+            .filter_map(|(validated_ledger, _, _)| *validated_ledger)
+            .max();
+        let now = Instant::now();
+        for (node, (validated_ledger, complete_ledgers, error)) in self.nodes.iter().zip(checks) {
+            let error = error.or_else(|| match (validated_ledger, tip) {
+                (Some(seq), Some(tip)) if seq + self.max_lag < tip => Some(format!(
+                    "validated ledger {} is {} behind {}",
+                    seq,
+                    tip - seq,
+                    tip
+                )),
+                (None, _) => Some("no validated ledger".to_string()),
+                _ => None,
+            });
+            let mut health = node.health.lock().unwrap();
+            health.healthy = error.is_none();
+            health.validated_ledger = validated_ledger;
+            health.last_error = error;
+            health.last_check = Some(now);
+            health.complete_ledgers = complete_ledgers;
+        }
+    }
+
+    /// Checks the health of the nodes every `interval` in a background task, which runs for
+    /// as long as the runtime does: dropping the returned handle does not stop it.
+    pub fn spawn_health_checks(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                self.check_health().await;
+                tokio::time::delay_for(interval).await;
+            }
+        })
+    }
+
+    /// The nodes to try for a request: round robin among the healthy ones, those known to
+    /// hold `ledger_index` first, then the full history nodes, then those not checked yet,
+    /// then any node as a last resort.
+    fn rotation(&self, ledger_index: Option<u64>) -> Vec<&Node> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.nodes.len();
+        let mut rotated: Vec<&Node> = self.nodes[start..]
+            .iter()
+            .chain(&self.nodes[..start])
+            .collect();
+        rotated.sort_by_key(|node| {
+            let health = node.health.lock().unwrap();
+            match ledger_index {
+                _ if !health.healthy => 4,
+                None => 0,
+                Some(ledger_index) if health.has_ledger(ledger_index) => 0,
+                Some(_) if node.full_history => 1,
+                Some(_) if health.last_check.is_none() => 2,
+                Some(_) => 3,
+            }
+        });
+        rotated
+    }
+}
+
+fn is_ledger_not_found(response: &Value) -> bool {
+    let error = XrpRpcError::from_response(response);
+    matches!(
+        error.as_ref().and_then(XrpRpcError::code),
+        Some(RippledErrorCode::LgrNotFound)
+    )
+}
+
+/// The ledger a request is about, when it names one by number.
+fn requested_ledger(params: &Value) -> Option<u64> {
+    match params.get("ledger_index")? {
+        Value::Number(ledger_index) => ledger_index.as_u64(),
+        Value::String(ledger_index) => ledger_index.parse().ok(),
+        _ => None,
+    }
+}
+
+impl Transport for NodePool {
+    fn request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Value, XrpRpcError>> {
+        Box::pin(async move {
+            let ledger_index = requested_ledger(&params);
+            let mut last_error = None;
+            let mut not_found = None;
+            for node in self.rotation(ledger_index) {
+                match node.transport.request(method, params.clone()).await {
+                    Ok(response) if ledger_index.is_some() && is_ledger_not_found(&response) => {
+                        not_found = Some(response)
+                    }
+                    Ok(response) => return Ok(response),
+                    Err(error) => {
+                        let mut health = node.health.lock().unwrap();
+                        health.healthy = false;
+                        health.last_error = Some(error.to_string());
+                        last_error = Some(error);
+                    }
+                }
+            }
+            if let Some(response) = not_found {
+                return Ok(response);
+            }
+            Err(last_error
+                .unwrap_or_else(|| XrpRpcError::Transport(anyhow!("The node pool has no nodes"))))
+        })
+    }
+}
+
+#[test]
+fn requested_ledger_test() {
+    assert_eq!(
+        requested_ledger(&json!({ "ledger_index": 32570 })),
+        Some(32570)
+    );
+    assert_eq!(
+        requested_ledger(&json!({ "ledger_index": "32570" })),
+        Some(32570)
+    );
+    assert_eq!(
+        requested_ledger(&json!({ "ledger_index": "validated" })),
+        None
+    );
+}
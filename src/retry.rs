@@ -0,0 +1,145 @@
+use crate::{RippledErrorCode, XrpRpcError};
+use rand::Rng;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+/// A kind of failure a [`RetryPolicy`] can choose to retry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+    /// The node answered with this error code
+    Rippled(RippledErrorCode),
+    /// The node answered with this HTTP status
+    HttpStatus(u16),
+    /// The connection was reset or dropped while waiting for the answer
+    ConnectionReset,
+}
+
+/// How [`crate::XRPClient`] retries the requests that only read, and so can be sent again
+/// safely: up to `max_attempts` tries, waiting an exponentially growing, jittered, time
+/// between them, for the failures in `retry_on` only.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of tries, the first one included
+    pub max_attempts: u32,
+    /// The wait before the first retry, doubled for every retry after it
+    pub initial_backoff: Duration,
+    /// The longest wait between two tries
+    pub max_backoff: Duration,
+    /// Waits a random time between half the backoff and the full backoff, so that clients
+    /// failing together do not retry together
+    pub jitter: bool,
+    /// The failures worth retrying
+    pub retry_on: HashSet<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    /// Five tries, starting at half a second, for the failures public nodes give when they
+    /// are loaded: `tooBusy`, `slowDown`, `noNetwork`, `notReady`, HTTP 503 and resets.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retry_on: vec![
+                RetryOn::Rippled(RippledErrorCode::TooBusy),
+                RetryOn::Rippled(RippledErrorCode::SlowDown),
+                RetryOn::Rippled(RippledErrorCode::NoNetwork),
+                RetryOn::Rippled(RippledErrorCode::NotReady),
+                RetryOn::HttpStatus(503),
+                RetryOn::ConnectionReset,
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether the error is one of the `retry_on` failures.
+    pub fn is_retryable(&self, error: &XrpRpcError) -> bool {
+        match error {
+            XrpRpcError::Rippled { code, .. } => {
+                self.retry_on.contains(&RetryOn::Rippled(code.clone()))
+            }
+            XrpRpcError::Http { status } => self.retry_on.contains(&RetryOn::HttpStatus(*status)),
+            XrpRpcError::Transport(_) => {
+                error.is_connection_reset() && self.retry_on.contains(&RetryOn::ConnectionReset)
+            }
+            XrpRpcError::Encode(_)
+            | XrpRpcError::Decode { .. }
+            | XrpRpcError::InvalidTransaction(_) => false,
+        }
+    }
+
+    /// How long to wait after the try number `attempt`, counting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << attempt.saturating_sub(1).min(31))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(rand::thread_rng().gen_range(0.5, 1.0))
+        } else {
+            backoff
+        }
+    }
+
+    /// Runs the request until it succeeds, fails with an error that is not retryable, or
+    /// runs out of tries.
+    pub(crate) async fn run<T, F, Fut>(&self, mut request: F) -> Result<T, XrpRpcError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, XrpRpcError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(error) if attempt < self.max_attempts && self.is_retryable(&error) => {
+                    tokio::time::delay_for(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[test]
+fn retry_policy_test() {
+    let policy = RetryPolicy {
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+    assert!(policy.is_retryable(&XrpRpcError::Http { status: 503 }));
+    assert!(!policy.is_retryable(&XrpRpcError::Http { status: 404 }));
+    assert!(policy.is_retryable(
+        &XrpRpcError::from_response(&serde_json::json!({
+            "error": "slowDown",
+            "status": "error"
+        }))
+        .unwrap()
+    ));
+    assert!(!policy.is_retryable(
+        &XrpRpcError::from_response(&serde_json::json!({
+            "error": "actNotFound",
+            "status": "error"
+        }))
+        .unwrap()
+    ));
+    assert!(policy.is_retryable(&XrpRpcError::Transport(
+        std::io::Error::from(std::io::ErrorKind::ConnectionReset).into()
+    )));
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+    assert_eq!(policy.backoff(3), Duration::from_secs(2));
+    assert_eq!(policy.backoff(10), Duration::from_secs(10));
+
+    let jittered = RetryPolicy::default();
+    for attempt in 1..8 {
+        let backoff = jittered.backoff(attempt);
+        let full = policy.backoff(attempt);
+        assert!(backoff >= full / 2 && backoff <= full);
+    }
+}
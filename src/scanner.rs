@@ -0,0 +1,168 @@
+use crate::{LedgerIndex, LedgerInfoParams, NestedLedgerInfo, XRPClient, XrpRpcError};
+use futures::stream::{self, Stream};
+use std::fmt;
+use std::time::Duration;
+
+/// Walks the validated ledgers one after the other, starting at a given ledger index, with
+/// the transactions of every ledger expanded. When it catches up with the network it waits
+/// for the next ledger to be validated instead of hammering the node.
+///
+/// Every ledger is checked to follow the one before it: its `parent_hash` has to be the
+/// `hash` of the previous ledger, otherwise a [`ScanError::ChainBreak`] is returned.
+#[derive(Debug, Clone)]
+pub struct LedgerScanner {
+    client: XRPClient,
+    next_index: u64,
+    previous_hash: Option<String>,
+    validated_index: u64,
+    poll_interval: Duration,
+}
+
+/// A ledger that does not follow the one scanned before it.
+#[derive(Debug, Clone)]
+pub struct ChainBreak {
+    /// The ledger that does not link to its predecessor
+    pub ledger_index: u64,
+    /// The hash of the ledger scanned before it
+    pub expected_parent_hash: String,
+    /// The parent hash the ledger claims to have
+    pub parent_hash: String,
+}
+
+impl fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ledger {} has parent hash {} but the previous ledger hash is {}",
+            self.ledger_index, self.parent_hash, self.expected_parent_hash
+        )
+    }
+}
+
+impl std::error::Error for ChainBreak {}
+
+/// Everything that can stop a [`LedgerScanner`].
+#[derive(Debug)]
+pub enum ScanError {
+    /// Asking the node failed
+    Rpc(XrpRpcError),
+    /// The ledger does not follow the one scanned before it
+    ChainBreak(ChainBreak),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScanError::Rpc(error) => error.fmt(f),
+            ScanError::ChainBreak(chain_break) => chain_break.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Rpc(error) => Some(error),
+            ScanError::ChainBreak(chain_break) => Some(chain_break),
+        }
+    }
+}
+
+impl From<XrpRpcError> for ScanError {
+    fn from(error: XrpRpcError) -> Self {
+        ScanError::Rpc(error)
+    }
+}
+
+impl LedgerScanner {
+    /// Waiting between two looks at the validated ledger when the scanner is at the tip, a
+    /// bit more than the usual time it takes the network to close a ledger.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(4);
+
+    /// A scanner whose first ledger will be `start_index`.
+    pub fn new(client: XRPClient, start_index: u64) -> Self {
+        LedgerScanner {
+            client,
+            next_index: start_index,
+            previous_hash: None,
+            validated_index: 0,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Changes how long to wait for a new validated ledger when the scanner is caught up.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The hash the parent of the next ledger is expected to have, to check the first ledger
+    /// of a scan that continues an earlier one.
+    pub fn with_previous_hash(mut self, previous_hash: String) -> Self {
+        self.previous_hash = Some(previous_hash);
+        self
+    }
+
+    /// The index of the ledger the next call to [`LedgerScanner::next_ledger`] returns.
+    pub fn next_index(&self) -> u64 {
+        self.next_index
+    }
+
+    /// Gets the next validated ledger, waiting for it to be validated if needed. On error
+    /// the scanner does not move, so calling it again retries the same ledger.
+    pub async fn next_ledger(&mut self) -> Result<NestedLedgerInfo, ScanError> {
+        loop {
+            if self.next_index > self.validated_index {
+                self.validated_index = self.client.validated_ledger_index().await?;
+                if self.next_index > self.validated_index {
+                    tokio::time::delay_for(self.poll_interval).await;
+                    continue;
+                }
+            }
+            let response = self
+                .client
+                .ledger(&LedgerInfoParams {
+                    ledger_hash: None,
+                    ledger_index: Some(LedgerIndex::Number {
+                        ledger_index: self.next_index.into(),
+                    }),
+                    full: None,
+                    accounts: None,
+                    transactions: Some(true),
+                    expand: Some(true),
+                    owner_funds: None,
+                    binary: None,
+                    queue: None,
+                })
+                .await?;
+            let ledger = match response.ledger {
+                Some(ledger) if response.validated => ledger,
+                _ => {
+                    tokio::time::delay_for(self.poll_interval).await;
+                    continue;
+                }
+            };
+            if let Some(expected_parent_hash) = &self.previous_hash {
+                if *expected_parent_hash != ledger.parent_hash {
+                    return Err(ScanError::ChainBreak(ChainBreak {
+                        ledger_index: self.next_index,
+                        expected_parent_hash: expected_parent_hash.clone(),
+                        parent_hash: ledger.parent_hash,
+                    }));
+                }
+            }
+            self.previous_hash = Some(ledger.hash.clone());
+            self.next_index += 1;
+            return Ok(ledger);
+        }
+    }
+
+    /// Turns the scanner into an endless stream of ledgers. The stream ends after the first
+    /// error it yields. It is not `Unpin`, so pin it before polling.
+    pub fn into_stream(self) -> impl Stream<Item = Result<NestedLedgerInfo, ScanError>> {
+        stream::try_unfold(self, |mut scanner| async move {
+            let ledger = scanner.next_ledger().await?;
+            Ok(Some((ledger, scanner)))
+        })
+    }
+}
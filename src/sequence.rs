@@ -0,0 +1,204 @@
+use crate::{
+    Account, AccountInfoParams, AccountObjectType, AccountObjectsParams, EngineResult, LedgerIndex,
+    LedgerObject, ResultCategory, XRPClient,
+};
+use anyhow::{anyhow, Result};
+use futures::lock::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// What a transaction of the account is numbered with: the next `Sequence` of the account,
+/// or one of its Tickets. See [1]
+///
+/// 1: https://xrpl.org/tickets.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountSequence {
+    /// Goes in the `Sequence` of the transaction
+    Sequence(u32),
+    /// Goes in the `TicketSequence` of the transaction, with a `Sequence` of 0
+    Ticket(u32),
+}
+
+impl AccountSequence {
+    /// Sets the `Sequence`, and the `TicketSequence` for a Ticket, of `transaction`.
+    pub fn fill<T: Serialize + DeserializeOwned>(&self, transaction: &T) -> Result<T> {
+        let mut transaction = serde_json::to_value(transaction)?;
+        match *self {
+            AccountSequence::Sequence(sequence) => {
+                transaction["Sequence"] = sequence.into();
+                if let Some(fields) = transaction.as_object_mut() {
+                    fields.remove("TicketSequence");
+                }
+            }
+            AccountSequence::Ticket(ticket_sequence) => {
+                transaction["Sequence"] = 0.into();
+                transaction["TicketSequence"] = ticket_sequence.into();
+            }
+        }
+        Ok(serde_json::from_value(transaction)?)
+    }
+}
+
+/// Hands out the sequence numbers of one account, so that transactions sent concurrently
+/// from it do not race for the same `Sequence`. The numbers are counted locally, starting
+/// from the account's next `Sequence` in `account_info`, after its queued transactions.
+/// Clones share the same numbers.
+///
+/// Every number handed out has to be given back to [`SequenceManager::report`] with the
+/// final result of its transaction, `tefMAX_LEDGER` for one that expired. A transaction
+/// that could not be applied leaves a gap that blocks the ones numbered after it, and
+/// `tefPAST_SEQ` or `terPRE_SEQ` mean the local count is off, so on those the count is taken
+/// again from `account_info` before the next number. The transactions that got such a result
+/// have to be signed again with a new number.
+///
+/// With [`SequenceManager::with_tickets`] the numbers are the account's Tickets instead, so
+/// a transaction that fails blocks no other one. A Ticket that was not used goes back to the
+/// others.
+#[derive(Debug, Clone)]
+pub struct SequenceManager {
+    client: XRPClient,
+    account: Account,
+    use_tickets: bool,
+    state: Arc<Mutex<SequenceState>>,
+}
+
+#[derive(Debug, Default)]
+struct SequenceState {
+    /// `None` until the count is taken from the ledger
+    next_sequence: Option<u32>,
+    /// `None` until the Tickets are taken from the ledger
+    tickets: Option<BTreeSet<u32>>,
+}
+
+impl SequenceManager {
+    /// A manager for the sequence numbers of `account`, with nothing fetched yet.
+    pub fn new(client: XRPClient, account: Account) -> Self {
+        SequenceManager {
+            client,
+            account,
+            use_tickets: false,
+            state: Arc::new(Mutex::new(SequenceState::default())),
+        }
+    }
+
+    /// Hands out the account's Tickets, lowest first, instead of sequence numbers. They have
+    /// to be created beforehand with a `TicketCreate` transaction.
+    pub fn with_tickets(mut self) -> Self {
+        self.use_tickets = true;
+        self
+    }
+
+    /// The number for the next transaction of the account. Fails when the account has no
+    /// Tickets left, with [`SequenceManager::with_tickets`].
+    pub async fn next(&self) -> Result<AccountSequence> {
+        let mut state = self.state.lock().await;
+        if self.use_tickets {
+            if state.tickets.is_none() {
+                state.tickets = Some(self.fetch_tickets().await?);
+            }
+            let tickets = state.tickets.get_or_insert_with(BTreeSet::new);
+            let ticket_sequence = *tickets
+                .iter()
+                .next()
+                .ok_or_else(|| anyhow!("No Tickets left for account {}", self.account))?;
+            tickets.remove(&ticket_sequence);
+            return Ok(AccountSequence::Ticket(ticket_sequence));
+        }
+        let sequence = match state.next_sequence {
+            Some(sequence) => sequence,
+            None => self.fetch_next_sequence().await?,
+        };
+        state.next_sequence = Some(sequence + 1);
+        Ok(AccountSequence::Sequence(sequence))
+    }
+
+    /// Takes the result of the transaction numbered with `sequence`, from `submit` or from a
+    /// validated ledger.
+    pub async fn report(&self, sequence: AccountSequence, result: &EngineResult) {
+        let mut state = self.state.lock().await;
+        let used = matches!(
+            result.category(),
+            Some(ResultCategory::Success) | Some(ResultCategory::ClaimedCost)
+        ) || *result == EngineResult::TerQueued;
+        match sequence {
+            AccountSequence::Sequence(_) if !used => state.next_sequence = None,
+            AccountSequence::Ticket(_) if *result == EngineResult::TerPreTicket => {
+                state.tickets = None
+            }
+            // tefNO_TICKET: the Ticket is gone already
+            AccountSequence::Ticket(ticket_sequence)
+                if !used && *result != EngineResult::TefNoTicket =>
+            {
+                if let Some(tickets) = state.tickets.as_mut() {
+                    tickets.insert(ticket_sequence);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the count, or the Tickets, from the ledger again, forgetting the numbers handed
+    /// out so far.
+    pub async fn resync(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if self.use_tickets {
+            state.tickets = Some(self.fetch_tickets().await?);
+        } else {
+            state.next_sequence = Some(self.fetch_next_sequence().await?);
+        }
+        Ok(())
+    }
+
+    async fn fetch_next_sequence(&self) -> Result<u32> {
+        // Queued transactions are only in the current ledger
+        let info = self
+            .client
+            .account_info(&AccountInfoParams {
+                account: &self.account,
+                strict: true,
+                ledger_index: LedgerIndex::StrValue {
+                    ledger_index: "current".into(),
+                },
+                queue: true,
+            })
+            .await?;
+        info.next_sequence()
+            .ok_or_else(|| anyhow!("No Sequence for account {}", self.account))
+    }
+
+    async fn fetch_tickets(&self) -> Result<BTreeSet<u32>> {
+        let mut tickets = BTreeSet::new();
+        let mut marker = None;
+        loop {
+            let objects = self
+                .client
+                .account_objects(&AccountObjectsParams {
+                    account: &self.account,
+                    object_type: Some(AccountObjectType::Ticket),
+                    deletion_blockers_only: None,
+                    ledger_hash: None,
+                    ledger_index: Some(LedgerIndex::StrValue {
+                        ledger_index: "current".into(),
+                    }),
+                    limit: Some(400),
+                    marker,
+                })
+                .await?;
+            tickets.extend(
+                objects
+                    .account_objects
+                    .into_iter()
+                    .filter_map(|object| match object {
+                        LedgerObject::Ticket(ticket) => Some(ticket.TicketSequence),
+                        _ => None,
+                    }),
+            );
+            marker = objects.marker;
+            if marker.is_none() {
+                return Ok(tickets);
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! What a node says about itself, through `server_info` and `server_state`. See [1] and [2]
+//! for the source of the documentation.
+//!
+//! 1: https://xrpl.org/server_info.html
+//! 2: https://xrpl.org/server_state.html
+
+use crate::Drops;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// How far a server takes part in the network. See [1]
+/// 1: https://xrpl.org/rippled-server-states.html
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatingMode {
+    ///Not connected to the peer-to-peer network at all.
+    Disconnected,
+    ///Connected to the network, but not yet following the ledgers.
+    Connected,
+    ///Catching up with the network.
+    Syncing,
+    ///Following the ledgers, but not sure they are the right ones yet.
+    Tracking,
+    ///In sync with the network.
+    Full,
+    ///In sync, and sending validations.
+    Validating,
+    ///In sync, and taking part in consensus.
+    Proposing,
+}
+
+impl OperatingMode {
+    /// Whether the server follows the network closely enough to be trusted with requests.
+    pub fn is_synced(&self) -> bool {
+        matches!(
+            self,
+            OperatingMode::Full | OperatingMode::Validating | OperatingMode::Proposing
+        )
+    }
+}
+
+///https://xrpl.org/server_info.html
+#[derive(Deserialize, Debug)]
+pub struct ServerInfo {
+    ///
+    pub info: NestedServerInfo,
+}
+
+///The numbers are human friendly, with amounts in XRP, see [`ServerState`] for the exact ones.
+#[derive(Deserialize, Debug)]
+pub struct NestedServerInfo {
+    ///Only present, and true, when the server lacks an enabled amendment and stopped following the network.
+    pub amendment_blocked: Option<bool>,
+    ///
+    pub build_version: String,
+    ///Range of ledgers that the server has available, like "32570-48293247", or "empty".
+    pub complete_ledgers: String,
+    ///
+    pub hostid: Option<String>,
+    ///
+    pub io_latency_ms: Option<u64>,
+    ///
+    pub last_close: Option<LastClose>,
+    ///The multiplier to the transaction cost the server is currently enforcing.
+    pub load_factor: f64,
+    ///
+    pub network_id: Option<u32>,
+    ///How many other rippled servers this one is connected to. Clio servers do not say.
+    pub peers: Option<u64>,
+    ///
+    pub pubkey_node: Option<String>,
+    ///Clio servers do not say.
+    pub server_state: Option<OperatingMode>,
+    ///Number of consecutive seconds that the server has been operational.
+    pub uptime: Option<u64>,
+    ///The latest fully-validated ledger, absent while the server has none.
+    pub validated_ledger: Option<ServerInfoValidatedLedger>,
+    ///
+    pub validation_quorum: Option<u32>,
+}
+
+impl NestedServerInfo {
+    /// The ledgers the server has available, see [`parse_complete_ledgers`].
+    pub fn complete_ledger_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        parse_complete_ledgers(&self.complete_ledgers)
+    }
+}
+
+///
+#[derive(Deserialize, Debug)]
+pub struct LastClose {
+    ///The amount of time, in seconds, it took to reach a consensus on the last ledger.
+    pub converge_time_s: f64,
+    ///How many trusted validators took part in the consensus of the last ledger.
+    pub proposers: u32,
+}
+
+///
+#[derive(Deserialize, Debug)]
+pub struct ServerInfoValidatedLedger {
+    ///The time since the ledger was closed, in seconds.
+    pub age: u64,
+    ///The base transaction cost, in XRP.
+    pub base_fee_xrp: BigDecimal,
+    ///
+    pub hash: String,
+    ///The minimum amount of XRP necessary for every account to keep in reserve.
+    pub reserve_base_xrp: BigDecimal,
+    ///The amount of XRP added to the account reserve for each object an account owns in the ledger.
+    pub reserve_inc_xrp: BigDecimal,
+    ///
+    pub seq: u64,
+}
+
+///https://xrpl.org/server_state.html
+#[derive(Deserialize, Debug)]
+pub struct ServerState {
+    ///
+    pub state: NestedServerState,
+}
+
+///The same as [`NestedServerInfo`], with the numbers as rippled keeps them: amounts in drops
+///and load factors against `load_base`.
+#[derive(Deserialize, Debug)]
+pub struct NestedServerState {
+    ///Only present, and true, when the server lacks an enabled amendment and stopped following the network.
+    pub amendment_blocked: Option<bool>,
+    ///
+    pub build_version: String,
+    ///Range of ledgers that the server has available, like "32570-48293247", or "empty".
+    pub complete_ledgers: String,
+    ///
+    pub io_latency_ms: Option<u64>,
+    ///The baseline the load factors are relative to.
+    pub load_base: u64,
+    ///The load factor the server is currently enforcing, against `load_base`.
+    pub load_factor: u64,
+    ///
+    pub load_factor_fee_escalation: Option<u64>,
+    ///
+    pub load_factor_fee_queue: Option<u64>,
+    ///
+    pub load_factor_fee_reference: Option<u64>,
+    ///
+    pub load_factor_server: Option<u64>,
+    ///
+    pub network_id: Option<u32>,
+    ///
+    pub peers: Option<u64>,
+    ///
+    pub pubkey_node: Option<String>,
+    ///
+    pub server_state: Option<OperatingMode>,
+    ///
+    pub uptime: Option<u64>,
+    ///
+    pub validated_ledger: Option<ServerStateValidatedLedger>,
+    ///
+    pub validation_quorum: Option<u32>,
+}
+
+impl NestedServerState {
+    /// The ledgers the server has available, see [`parse_complete_ledgers`].
+    pub fn complete_ledger_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        parse_complete_ledgers(&self.complete_ledgers)
+    }
+}
+
+///
+#[derive(Deserialize, Debug)]
+pub struct ServerStateValidatedLedger {
+    ///The base transaction cost, in drops of XRP.
+    pub base_fee: Drops,
+    ///The time this ledger was closed, in seconds since the Ripple Epoch
+    pub close_time: u64,
+    ///
+    pub hash: String,
+    ///The minimum reserve, in drops of XRP, that is required for an account.
+    pub reserve_base: Drops,
+    ///The owner reserve for each object an account owns in the ledger, in drops of XRP.
+    pub reserve_inc: Drops,
+    ///
+    pub seq: u64,
+}
+
+/// Parses a range list like "32570-48293247,48293250-48293300", rippled says "empty" when it
+/// holds no ledger.
+pub fn parse_complete_ledgers(complete_ledgers: &str) -> Vec<RangeInclusive<u64>> {
+    complete_ledgers
+        .split(',')
+        .filter_map(|range| {
+            let mut bounds = range.trim().splitn(2, '-');
+            let start = bounds.next()?.parse().ok()?;
+            let end = match bounds.next() {
+                Some(end) => end.parse().ok()?,
+                None => start,
+            };
+            Some(start..=end)
+        })
+        .collect()
+}
+
+#[test]
+fn server_info_test() {
+    let info: ServerInfo = serde_json::from_str(
+        r#"{
+            "info": {
+                "build_version": "1.12.0",
+                "complete_ledgers": "32570-48293247,48293250",
+                "hostid": "LEST",
+                "io_latency_ms": 1,
+                "last_close": { "converge_time_s": 3.002, "proposers": 35 },
+                "load_factor": 1.5,
+                "peers": 21,
+                "pubkey_node": "n9KUjqxCr5FKThSNXdzb7oqN8rYwScB2dUnNqxQxbEA17JkaWy5x",
+                "server_state": "full",
+                "uptime": 3590,
+                "validated_ledger": {
+                    "age": 2,
+                    "base_fee_xrp": 0.00001,
+                    "hash": "C84E2AD5C98978C1B181CF117D0B824E98D3971BA0B2174AACA9D3A5835A99DA",
+                    "reserve_base_xrp": 10,
+                    "reserve_inc_xrp": 2,
+                    "seq": 48293247
+                },
+                "validation_quorum": 28
+            },
+            "status": "success"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(info.info.server_state, Some(OperatingMode::Full));
+    assert!(info.info.server_state.unwrap().is_synced());
+    assert_eq!(
+        info.info.complete_ledger_ranges(),
+        vec![32570..=48293247, 48293250..=48293250]
+    );
+    let validated_ledger = info.info.validated_ledger.unwrap();
+    assert_eq!(validated_ledger.seq, 48293247);
+    assert_eq!(validated_ledger.reserve_base_xrp, BigDecimal::from(10));
+    assert_eq!(
+        validated_ledger.base_fee_xrp,
+        "0.00001".parse::<BigDecimal>().unwrap()
+    );
+    assert!(parse_complete_ledgers("empty").is_empty());
+
+    let state: ServerState = serde_json::from_str(
+        r#"{
+            "state": {
+                "build_version": "1.12.0",
+                "complete_ledgers": "empty",
+                "load_base": 256,
+                "load_factor": 256,
+                "peers": 0,
+                "server_state": "syncing",
+                "uptime": 12
+            },
+            "status": "success"
+        }"#,
+    )
+    .unwrap();
+    assert!(!state.state.server_state.unwrap().is_synced());
+    assert!(state.state.complete_ledger_ranges().is_empty());
+    assert!(state.state.validated_ledger.is_none());
+}
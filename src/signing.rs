@@ -0,0 +1,342 @@
+//! Signs transactions offline, so that secrets never have to go to rippled's `sign`. See [1]
+//! for the source of the documentation.
+//!
+//! 1: https://xrpl.org/serialization.html#signing-and-hashing
+
+use crate::codec::{to_binary, to_signing_binary, transaction_hash};
+use crate::{Account, Keypair, PublicKey};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The prefix of a transaction when it is signed by a single key, "STX\0".
+const SINGLE_SIGNING_PREFIX: [u8; 4] = [0x53, 0x54, 0x58, 0x00];
+/// The prefix of a transaction when it is signed by one of many signers, "SMT\0".
+const MULTI_SIGNING_PREFIX: [u8; 4] = [0x53, 0x4D, 0x54, 0x00];
+
+/// A signed transaction, ready for `submit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    ///The binary transaction, in hex.
+    pub tx_blob: String,
+    ///The ID of the transaction, to look it up with `tx` once it is submitted.
+    pub hash: String,
+}
+
+/// Signs `transaction` with `keypair`, the master key or the regular key of its `Account`.
+/// The `Fee` and the `Sequence` have to be set already, the `SigningPubKey` and the
+/// `TxnSignature` are set here.
+pub fn sign_transaction<T: Serialize>(
+    transaction: &T,
+    keypair: &Keypair,
+) -> Result<SignedTransaction> {
+    let mut transaction = unsigned(transaction, keypair.public_key().to_string())?;
+    let mut signing_data = SINGLE_SIGNING_PREFIX.to_vec();
+    signing_data.extend(to_signing_binary(&transaction)?);
+    transaction["TxnSignature"] = hex::encode_upper(keypair.sign(&signing_data)).into();
+    let tx_blob = to_binary(&transaction)?;
+    Ok(SignedTransaction {
+        hash: transaction_hash(&tx_blob),
+        tx_blob: hex::encode_upper(tx_blob),
+    })
+}
+
+/// The signature of one of the signers of a multi-signed transaction. See [1]
+/// 1: https://xrpl.org/multi-signing.html
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Signer {
+    ///The signer, an account of the SignerList of the account sending the transaction.
+    pub Account: Account,
+    ///The key that signed, the master key or the regular key of the signer.
+    pub SigningPubKey: String,
+    ///
+    pub TxnSignature: String,
+}
+
+///The ledger wraps every entry of a `Signers` array in a `Signer` object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignerWrapper {
+    ///
+    pub Signer: Signer,
+}
+
+/// A multi-signed transaction, with its signatures in `Signers`.
+#[derive(Debug, Clone)]
+pub struct MultisignedTransaction {
+    ///The transaction as JSON, what `submit_multisigned` takes.
+    pub tx_json: Value,
+    ///The binary transaction, in hex, which `submit` also takes.
+    pub tx_blob: String,
+    ///The ID of the transaction, to look it up with `tx` once it is submitted.
+    pub hash: String,
+    ///The signers, in the order of `Signers`.
+    pub signers: Vec<Signer>,
+}
+
+/// The transaction as JSON without its signature, ready to be signed by `signing_pub_key`,
+/// which is empty for multi-signed transactions.
+fn unsigned<T: Serialize>(transaction: &T, signing_pub_key: String) -> Result<Value> {
+    let mut transaction = serde_json::to_value(transaction)?;
+    let object = transaction
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("A transaction is an object"))?;
+    for field in &["Account", "TransactionType", "Fee", "Sequence"] {
+        if matches!(object.get(*field), None | Some(Value::Null)) {
+            return Err(anyhow!("The transaction has no {}", field));
+        }
+    }
+    object.insert("SigningPubKey".into(), signing_pub_key.into());
+    object.remove("TxnSignature");
+    Ok(transaction)
+}
+
+/// Signs `transaction` as `signer`, one of the accounts of the SignerList of its `Account`,
+/// with `keypair`, the master key or the regular key of `signer`. The signatures of the
+/// signers are put together with [`combine_signers`]. The `Fee` has to pay for every
+/// signature, see [1]
+///
+/// 1: https://xrpl.org/transaction-cost.html#special-transaction-costs
+pub fn multisign_transaction<T: Serialize>(
+    transaction: &T,
+    signer: &Account,
+    keypair: &Keypair,
+) -> Result<Signer> {
+    let mut signing_data = multi_signing_data(&unsigned(transaction, String::new())?)?;
+    // Every signer signs something different, so that a signature is only good for its signer
+    signing_data.extend_from_slice(&signer.account_id());
+    Ok(Signer {
+        Account: signer.clone(),
+        SigningPubKey: keypair.public_key().to_string(),
+        TxnSignature: hex::encode_upper(keypair.sign(&signing_data)),
+    })
+}
+
+/// What every signer signs, before its account ID.
+fn multi_signing_data(unsigned: &Value) -> Result<Vec<u8>> {
+    let mut signing_data = MULTI_SIGNING_PREFIX.to_vec();
+    signing_data.extend(to_signing_binary(unsigned)?);
+    Ok(signing_data)
+}
+
+/// Puts the signatures of `signers` in the `Signers` of `transaction`, along with the ones
+/// it already has, in the order the ledger requires: by account ID. An account can only
+/// sign once, and every signature has to be valid.
+pub fn combine_signers<T: Serialize>(
+    transaction: &T,
+    signers: &[Signer],
+) -> Result<MultisignedTransaction> {
+    let existing: Option<Vec<SignerWrapper>> = match serde_json::to_value(transaction)? {
+        Value::Object(mut object) => match object.remove("Signers") {
+            Some(Value::Null) | None => None,
+            Some(existing) => Some(serde_json::from_value(existing)?),
+        },
+        _ => None,
+    };
+    let mut all_signers: Vec<Signer> = existing
+        .into_iter()
+        .flatten()
+        .map(|wrapper| wrapper.Signer)
+        .chain(signers.iter().cloned())
+        .collect();
+    all_signers.sort_by_key(|signer| signer.Account.account_id());
+    all_signers.dedup();
+    if let Some(pair) = all_signers
+        .windows(2)
+        .find(|pair| pair[0].Account == pair[1].Account)
+    {
+        return Err(anyhow!("{} signed twice", pair[0].Account));
+    }
+    if all_signers.is_empty() {
+        return Err(anyhow!(
+            "A multi-signed transaction needs at least one signer"
+        ));
+    }
+    let mut tx_json = unsigned(transaction, String::new())?;
+    let signing_data = multi_signing_data(&tx_json)?;
+    for signer in &all_signers {
+        let mut data = signing_data.clone();
+        data.extend_from_slice(&signer.Account.account_id());
+        let valid = match (
+            signer.SigningPubKey.parse::<PublicKey>(),
+            hex::decode(&signer.TxnSignature),
+        ) {
+            (Ok(public_key), Ok(signature)) => public_key.verify(&data, &signature),
+            _ => false,
+        };
+        if !valid {
+            return Err(anyhow!("The signature of {} is not valid", signer.Account));
+        }
+    }
+    tx_json["Signers"] = serde_json::to_value(
+        all_signers
+            .iter()
+            .cloned()
+            .map(|signer| SignerWrapper { Signer: signer })
+            .collect::<Vec<_>>(),
+    )?;
+    let tx_blob = to_binary(&tx_json)?;
+    Ok(MultisignedTransaction {
+        tx_json,
+        hash: transaction_hash(&tx_blob),
+        tx_blob: hex::encode_upper(tx_blob),
+        signers: all_signers,
+    })
+}
+
+#[test]
+fn sign_transaction_test() {
+    use crate::{Balance, Drops, Payment, Seed, TransactionInfo};
+
+    // The AccountSet signed in the tests of xrpl-py and xrpl-rust
+    let keypair = "sEdT7wHTCLzDG7ueaw4hroSTBvH7Mk5"
+        .parse::<Seed>()
+        .unwrap()
+        .derive_keypair();
+    let account_set = serde_json::json!({
+        "Account": keypair.account(),
+        "Domain": "6578616d706c652e636f6d",
+        "Fee": "10",
+        "Flags": 0,
+        "Sequence": 227234,
+        "TransactionType": "AccountSet",
+    });
+    let signed = sign_transaction(&account_set, &keypair).unwrap();
+    let transaction: Value = crate::from_binary(&hex::decode(&signed.tx_blob).unwrap()).unwrap();
+    assert_eq!(
+        transaction["TxnSignature"],
+        "C3F435CFBFAE996FE297F3A71BEAB68FF5322CBF039E41A9615BC48A59FB4EC5A55F8D4EC0225D47056E02ECCCDF7E8FF5F8B7FAA1EBBCBF7D0491FCB2D98807"
+    );
+
+    // The secp256k1 AccountSet signed in the tests of xrpl.js, which pins the DER encoding
+    // and the low S of the signature
+    let keypair = "shsWGZcmZz6YsWWmcnpfr6fLTdtFV"
+        .parse::<Seed>()
+        .unwrap()
+        .derive_keypair();
+    let account_set = serde_json::json!({
+        "Account": "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59",
+        "Domain": "726970706C652E636F6D",
+        "Fee": "12",
+        "Flags": 2147483648u32,
+        "LastLedgerSequence": 8820051,
+        "Sequence": 23,
+        "TransactionType": "AccountSet",
+    });
+    let signed = sign_transaction(&account_set, &keypair).unwrap();
+    assert_eq!(
+        signed,
+        SignedTransaction {
+            tx_blob: "12000322800000002400000017201B0086955368400000000000000C732102F89EAEC7667B30F33D0687BBA86C3FE2A08CCA40A9186C5BDE2DAA6FA97A37D874473045022100BDE09A1F6670403F341C21A77CF35BA47E45CDE974096E1AA5FC39811D8269E702203D60291B9A27F1DCABA9CF5DED307B4F23223E0B6F156991DB601DFB9C41CE1C770A726970706C652E636F6D81145E7B112523F68D2F5E879DB4EAC51C6698A69304".into(),
+            hash: "02ACE87F1996E3A23690A5BB7F1774BF71CCBA68F79805831B42ABAD5913D6F4".into(),
+        }
+    );
+    let transaction: Value = crate::from_binary(&hex::decode(&signed.tx_blob).unwrap()).unwrap();
+    let signature = hex::decode(transaction["TxnSignature"].as_str().unwrap()).unwrap();
+    let mut normalized = secp256k1::Signature::from_der(&signature).unwrap();
+    normalized.normalize_s();
+    assert_eq!(normalized.serialize_der().to_vec(), signature);
+
+    let keypair = "snoPBrXtMeMyMHUVTgbuqAfg1SUTb"
+        .parse::<Seed>()
+        .unwrap()
+        .derive_keypair();
+    let mut payment = Payment::new(
+        keypair.account(),
+        "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf".parse().unwrap(),
+        Balance::XRP(Drops::from_xrp(1000).unwrap()),
+    );
+    payment.common.Sequence = Some(1);
+    assert!(sign_transaction(&payment, &keypair).is_err());
+    payment.common.Fee = Some(Drops::from_drops(12).unwrap());
+    let signed = sign_transaction(&payment, &keypair).unwrap();
+    assert_eq!(
+        signed,
+        sign_transaction(&payment, &keypair).unwrap(),
+        "secp256k1 signatures are deterministic"
+    );
+    let transaction = TransactionInfo::from_tx_blob(&signed.tx_blob).unwrap();
+    assert_eq!(transaction.hash, signed.hash);
+    assert_eq!(transaction.signing_account(), Some(keypair.account()));
+    payment.common.SigningPubKey = Some(keypair.public_key().to_string());
+    let mut signing_data = SINGLE_SIGNING_PREFIX.to_vec();
+    signing_data.extend(to_signing_binary(&payment).unwrap());
+    let signature = hex::decode(
+        transaction
+            .transaction
+            .common()
+            .unwrap()
+            .TxnSignature
+            .as_ref()
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(keypair.public_key().verify(&signing_data, &signature));
+}
+
+#[test]
+fn multisign_transaction_test() {
+    use crate::Seed;
+
+    let keypair = |seed: &str| seed.parse::<Seed>().unwrap().derive_keypair();
+    // The AccountSet multi-signed in the tests of xrpl-py and xrpl-rust
+    let account_set = serde_json::json!({
+        "Account": keypair("sEdSkooMk31MeTjbHVE7vLvgCpEMAdB").account(),
+        "Domain": "6578616d706c652e636f6d",
+        "Fee": "40",
+        "Flags": 0,
+        "LastLedgerSequence": 4814775,
+        "Sequence": 4814738,
+        "TransactionType": "AccountSet",
+    });
+    let first = keypair("sEdTLQkHAWpdS7FDk7EvuS7Mz8aSMRh");
+    let first = multisign_transaction(&account_set, &first.account(), &first).unwrap();
+    assert_eq!(
+        first.TxnSignature,
+        "E3BEF86AEFC61E5ED66C95D0C5CE699721A8DAF86B6ED0D1CBAC86C2C03D96A098767B4F163FADBD937A99AC40BD6CED16B2CA98B198C2343D4BA31ECE57530C"
+    );
+    let second = keypair("sEd7DXaHkGQD8mz8xcRLDxfMLqCurif");
+    let second = multisign_transaction(&account_set, &second.account(), &second).unwrap();
+    assert_eq!(
+        second.TxnSignature,
+        "DB64FC69F34A4881F6087226681E7BDDB212027B3FAFB617E598DCA5BBC8FA1A15A6E37A760B534BA554FBCD8D4A9FDEC8DFED206E3EBC393B875F59C765D304"
+    );
+
+    // Whatever order they come in, and whoever already signed
+    let partial = combine_signers(&account_set, std::slice::from_ref(&second)).unwrap();
+    let multisigned = combine_signers(&partial.tx_json, std::slice::from_ref(&first)).unwrap();
+    assert_eq!(
+        multisigned.tx_blob,
+        combine_signers(&account_set, &[second.clone(), first.clone()])
+            .unwrap()
+            .tx_blob
+    );
+    let signers = &multisigned.signers;
+    assert_eq!(signers.len(), 2);
+    assert!(signers[0].Account.account_id() < signers[1].Account.account_id());
+    assert_eq!(multisigned.tx_json["SigningPubKey"], "");
+    let transaction: Value =
+        crate::from_binary(&hex::decode(&multisigned.tx_blob).unwrap()).unwrap();
+    assert_eq!(transaction["Signers"], multisigned.tx_json["Signers"]);
+
+    let signer_list: crate::SignerListObject = serde_json::from_value(serde_json::json!({
+        "Flags": 0,
+        "OwnerNode": "0000000000000000",
+        "PreviousTxnID": "5904C0DC72C58A83AEFED2FFC5386356AA83FCA6A88C89D00646E51E687CDBE4",
+        "PreviousTxnLgrSeq": 16061435,
+        "SignerEntries": [
+            { "SignerEntry": { "Account": first.Account, "SignerWeight": 1 } },
+            { "SignerEntry": { "Account": second.Account, "SignerWeight": 1 } }
+        ],
+        "SignerListID": 0,
+        "SignerQuorum": 2,
+        "index": "A9C28A28B85CD533217F5C0A0C7767666B093FA58A0F2D80026FCC4CD932DDC7"
+    }))
+    .unwrap();
+    assert!(signer_list.check_quorum(&multisigned.signers).is_ok());
+    assert!(signer_list.check_quorum(&partial.signers).is_err());
+
+    let mut forged = first.clone();
+    forged.TxnSignature = second.TxnSignature.clone();
+    assert!(combine_signers(&account_set, &[forged.clone()]).is_err());
+    assert!(combine_signers(&multisigned.tx_json, &[forged]).is_err());
+}
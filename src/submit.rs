@@ -0,0 +1,358 @@
+//! Sending signed transactions to the network, and following them until their outcome is
+//! final. See [1] and [2] for the source of the documentation.
+//!
+//! 1: https://xrpl.org/submit.html
+//! 2: https://xrpl.org/reliable-transaction-submission.html
+
+use crate::{sign_transaction, Keypair, TxResult, XRPClient};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+macro_rules! engine_results {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)*) => {
+        /// The result of applying a transaction, like `tesSUCCESS`. See [1]
+        /// 1: https://xrpl.org/transaction-results.html
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum EngineResult {
+            $($(#[$doc])* $variant,)*
+            /// A code this crate does not know about
+            Other(String),
+        }
+
+        impl EngineResult {
+            /// The result of its code.
+            pub fn from_code(code: &str) -> Self {
+                match code {
+                    $($code => EngineResult::$variant,)*
+                    other => EngineResult::Other(other.to_string()),
+                }
+            }
+
+            /// The code as rippled spells it.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(EngineResult::$variant => $code,)*
+                    EngineResult::Other(other) => other,
+                }
+            }
+        }
+    };
+}
+
+engine_results! {
+    ///The transaction was applied.
+    TesSuccess => "tesSUCCESS",
+    ///The transaction only took the fee, because of its own flags or a failure of rippled.
+    TecClaim => "tecCLAIM",
+    ///The destination account does not exist, and the payment does not create it.
+    TecNoDst => "tecNO_DST",
+    ///The payment is too small to create the destination account.
+    TecNoDstInsufXrp => "tecNO_DST_INSUF_XRP",
+    ///The destination requires a destination tag.
+    TecDstTagNeeded => "tecDST_TAG_NEEDED",
+    ///The destination does not accept payments from the sender.
+    TecNoPermission => "tecNO_PERMISSION",
+    ///The sender does not have the reserve for the object the transaction creates.
+    TecInsufficientReserve => "tecINSUFFICIENT_RESERVE",
+    ///The payment could not deliver its full amount.
+    TecPathPartial => "tecPATH_PARTIAL",
+    ///No path has liquidity for the payment.
+    TecPathDry => "tecPATH_DRY",
+    ///The sender does not hold enough of what it sends.
+    TecUnfundedPayment => "tecUNFUNDED_PAYMENT",
+    ///The transaction is already in the ledger.
+    TefAlready => "tefALREADY",
+    ///The key is not authorized to sign for the account.
+    TefBadAuth => "tefBAD_AUTH",
+    ///The signers do not reach the quorum of the SignerList.
+    TefBadQuorum => "tefBAD_QUORUM",
+    ///A signer is not on the SignerList.
+    TefBadSignature => "tefBAD_SIGNATURE",
+    ///The master key is disabled.
+    TefMasterDisabled => "tefMASTER_DISABLED",
+    ///The `LastLedgerSequence` has already passed.
+    TefMaxLedger => "tefMAX_LEDGER",
+    ///The account has no SignerList.
+    TefNotMultiSigning => "tefNOT_MULTI_SIGNING",
+    ///The `Sequence` is lower than the account's, it is used already.
+    TefPastSeq => "tefPAST_SEQ",
+    ///The `TicketSequence` is not a Ticket of the account.
+    TefNoTicket => "tefNO_TICKET",
+    ///The fee is too low for the current load of the server.
+    TelInsufFeeP => "telINSUF_FEE_P",
+    ///The open ledger is full and the transaction could not be queued.
+    TelCanNotQueue => "telCAN_NOT_QUEUE",
+    ///The fee is too low to replace the transaction in the queue.
+    TelCanNotQueueFee => "telCAN_NOT_QUEUE_FEE",
+    ///The queue is full.
+    TelCanNotQueueFull => "telCAN_NOT_QUEUE_FULL",
+    ///The transaction is malformed.
+    TemMalformed => "temMALFORMED",
+    ///The fee is invalid.
+    TemBadFee => "temBAD_FEE",
+    ///The amount is invalid.
+    TemBadAmount => "temBAD_AMOUNT",
+    ///The signature is invalid.
+    TemBadSignature => "temBAD_SIGNATURE",
+    ///The transaction would do nothing, like a payment to its own account.
+    TemRedundant => "temREDUNDANT",
+    ///The `Sequence` is higher than the account's next one, the ones before have to come first.
+    TerPreSeq => "terPRE_SEQ",
+    ///The `TicketSequence` is a Ticket that does not exist yet.
+    TerPreTicket => "terPRE_TICKET",
+    ///The account cannot pay the fee.
+    TerInsufFeeB => "terINSUF_FEE_B",
+    ///The account does not exist.
+    TerNoAccount => "terNO_ACCOUNT",
+    ///The transaction was queued, to be applied to a later ledger.
+    TerQueued => "terQUEUED",
+    ///The transaction could not be applied yet.
+    TerRetry => "terRETRY",
+}
+
+/// What a result says about the transaction, from the prefix of its code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultCategory {
+    /// `tes`: applied
+    Success,
+    /// `tec`: applied, but it only took the fee
+    ClaimedCost,
+    /// `tef`: not applied, and applying it again would fail too
+    Failure,
+    /// `tel`: not applied by this server, and not relayed to others
+    LocalError,
+    /// `tem`: malformed, it can never be applied
+    Malformed,
+    /// `ter`: not applied yet, it could be later
+    Retry,
+}
+
+impl EngineResult {
+    /// The category of the result, `None` for a code that has none of the known prefixes.
+    pub fn category(&self) -> Option<ResultCategory> {
+        match self.as_str().get(..3)? {
+            "tes" => Some(ResultCategory::Success),
+            "tec" => Some(ResultCategory::ClaimedCost),
+            "tef" => Some(ResultCategory::Failure),
+            "tel" => Some(ResultCategory::LocalError),
+            "tem" => Some(ResultCategory::Malformed),
+            "ter" => Some(ResultCategory::Retry),
+            _ => None,
+        }
+    }
+
+    /// Whether submitting the same transaction again could get it applied: a `ter` result
+    /// other than `terQUEUED`, or a `tel` result.
+    pub fn is_retriable(&self) -> bool {
+        match self.category() {
+            Some(ResultCategory::Retry) => *self != EngineResult::TerQueued,
+            Some(ResultCategory::LocalError) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for EngineResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for EngineResult {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EngineResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|code| EngineResult::from_code(&code))
+    }
+}
+
+/// What the node says of a transaction it was given, by `submit` or `submit_multisigned`.
+/// The result is preliminary: only a validated ledger makes it final.
+#[derive(Deserialize, Debug)]
+pub struct Submit {
+    ///The preliminary result of the transaction.
+    pub engine_result: EngineResult,
+    ///
+    pub engine_result_code: i32,
+    ///
+    pub engine_result_message: String,
+    ///The binary transaction that was submitted, in hex.
+    pub tx_blob: String,
+    ///The transaction that was submitted, as JSON, with its `hash`.
+    pub tx_json: serde_json::Value,
+}
+
+/// The final outcome of a transaction sent by the [`ReliableSubmitter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    /// The transaction is in a validated ledger. Only a `tesSUCCESS` result did what the
+    /// transaction asked, a `tec` result only took the fee.
+    Validated {
+        ///
+        result: EngineResult,
+        ///The ledger the transaction is in.
+        ledger_index: u64,
+        ///
+        hash: String,
+    },
+    /// Its `LastLedgerSequence` is validated and the transaction is in none of the ledgers
+    /// up to it, so it can never be applied.
+    Expired {
+        ///
+        hash: String,
+    },
+    /// The transaction was never applied and never can be, like a malformed one.
+    Rejected {
+        ///The result that rejected the transaction.
+        result: EngineResult,
+        ///
+        hash: String,
+    },
+}
+
+/// Sends transactions the way xrpl.org recommends, so that they are applied at most once and
+/// their final outcome is known: every transaction gets a `LastLedgerSequence`, is sent again
+/// while its result says it could still be applied, and is followed through the validated
+/// ledgers until it is in one of them or its `LastLedgerSequence` has passed.
+#[derive(Debug, Clone)]
+pub struct ReliableSubmitter {
+    client: XRPClient,
+    ledger_offset: u32,
+    poll_interval: Duration,
+    max_polls_past_last_ledger: u32,
+}
+
+impl ReliableSubmitter {
+    /// How many ledgers after the current validated ledger a transaction can make it into.
+    pub const DEFAULT_LEDGER_OFFSET: u32 = 20;
+    /// Waiting between two looks at the transaction, about the time it takes the network to
+    /// close a ledger.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(4);
+    /// How many looks at a transaction past its last ledger wait for the node to have every
+    /// ledger it could be in, before giving up.
+    pub const DEFAULT_MAX_POLLS_PAST_LAST_LEDGER: u32 = 10;
+
+    ///
+    pub fn new(client: XRPClient) -> Self {
+        ReliableSubmitter {
+            client,
+            ledger_offset: Self::DEFAULT_LEDGER_OFFSET,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            max_polls_past_last_ledger: Self::DEFAULT_MAX_POLLS_PAST_LAST_LEDGER,
+        }
+    }
+
+    /// Changes how many ledgers after the current validated ledger the `LastLedgerSequence`
+    /// of the transactions is.
+    pub fn with_ledger_offset(mut self, ledger_offset: u32) -> Self {
+        self.ledger_offset = ledger_offset;
+        self
+    }
+
+    /// Changes how long to wait between two looks at a transaction.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Changes how many looks at a transaction past its last ledger wait for the node to have
+    /// every ledger it could be in. A node missing some of them never says the transaction
+    /// is not in any, so another node has to be asked.
+    pub fn with_max_polls_past_last_ledger(mut self, max_polls_past_last_ledger: u32) -> Self {
+        self.max_polls_past_last_ledger = max_polls_past_last_ledger;
+        self
+    }
+
+    /// Sets the `LastLedgerSequence` of `transaction`, signs it with `keypair` and sends it,
+    /// then follows it until its outcome is final. The `Fee` and the `Sequence` have to be
+    /// set already. An error means the outcome is not known yet: the transaction may still
+    /// make it into a ledger, so look it up by its hash before sending it again.
+    pub async fn submit<T: Serialize>(
+        &self,
+        transaction: &T,
+        keypair: &Keypair,
+    ) -> Result<SubmitOutcome> {
+        let start_index = u32::try_from(self.client.validated_ledger_index().await?)?;
+        let last_ledger_sequence = start_index
+            .checked_add(self.ledger_offset)
+            .ok_or_else(|| anyhow!("No LastLedgerSequence after ledger {}", start_index))?;
+        let mut transaction = serde_json::to_value(transaction)?;
+        transaction["LastLedgerSequence"] = last_ledger_sequence.into();
+        let signed = sign_transaction(&transaction, keypair)?;
+        let hash = signed.hash.clone();
+
+        let mut result = self.client.submit(&signed.tx_blob).await?.engine_result;
+        let mut polls_past_last_ledger = 0;
+        loop {
+            if result.category() == Some(ResultCategory::Malformed) {
+                // The same transaction could not have been applied by an earlier submission
+                return Ok(SubmitOutcome::Rejected { result, hash });
+            }
+            tokio::time::delay_for(self.poll_interval).await;
+            let validated_index = self.client.validated_ledger_index().await?;
+            let past_last_ledger = validated_index >= u64::from(last_ledger_sequence);
+            // Past its last ledger, the node has to say it looked at every ledger it could be in
+            let (min_ledger, max_ledger) = if past_last_ledger {
+                (Some(start_index), Some(last_ledger_sequence))
+            } else {
+                (None, None)
+            };
+            match self.client.tx(&hash, false, min_ledger, max_ledger).await? {
+                TxResult::Found(tx) if tx.transaction.validated == Some(true) => {
+                    let meta = tx
+                        .meta
+                        .ok_or_else(|| anyhow!("Validated transaction {} has no meta", hash))?;
+                    return Ok(SubmitOutcome::Validated {
+                        result: EngineResult::from_code(&meta.TransactionResult),
+                        ledger_index: tx.ledger_index.unwrap_or(validated_index),
+                        hash,
+                    });
+                }
+                TxResult::NotFound { searched_all: true } if past_last_ledger => {
+                    return Ok(match result.category() {
+                        Some(ResultCategory::Failure) => SubmitOutcome::Rejected { result, hash },
+                        _ => SubmitOutcome::Expired { hash },
+                    });
+                }
+                _ if past_last_ledger => {
+                    polls_past_last_ledger += 1;
+                    if polls_past_last_ledger >= self.max_polls_past_last_ledger {
+                        return Err(anyhow!(
+                            "Transaction {} is past its last ledger {}, but the node does not have \
+                             every ledger since {}",
+                            hash,
+                            last_ledger_sequence,
+                            start_index
+                        ));
+                    }
+                }
+                _ => {}
+            }
+            if result.is_retriable() && !past_last_ledger {
+                result = self.client.submit(&signed.tx_blob).await?.engine_result;
+            }
+        }
+    }
+}
+
+#[test]
+fn engine_result_test() {
+    let result: EngineResult = serde_json::from_str(r#""terQUEUED""#).unwrap();
+    assert_eq!(result, EngineResult::TerQueued);
+    assert_eq!(result.category(), Some(ResultCategory::Retry));
+    assert!(!result.is_retriable());
+    assert!(EngineResult::TerPreSeq.is_retriable());
+    assert!(EngineResult::TelInsufFeeP.is_retriable());
+    assert!(!EngineResult::TefPastSeq.is_retriable());
+    let result = EngineResult::from_code("tecSOMETHING_NEW");
+    assert_eq!(result, EngineResult::Other("tecSOMETHING_NEW".into()));
+    assert_eq!(result.category(), Some(ResultCategory::ClaimedCost));
+    assert_eq!(result.to_string(), "tecSOMETHING_NEW");
+}
@@ -0,0 +1,867 @@
+//! Transactions to build, sign and submit, and to read from the ledger. See [1] for the
+//! source of the documentation.
+//!
+//! 1: https://xrpl.org/transaction-types.html
+
+use crate::{
+    Account, AuthAccountWrapper, Balance, Drops, Issue, PathInfo, SignerEntryWrapper, SignerWrapper,
+};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// The fields every transaction has, whatever its type. See [1]
+/// 1: https://xrpl.org/transaction-common-fields.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommonFields {
+    ///The account sending the transaction.
+    pub Account: Account,
+    ///The hash of the previous transaction of the account, which this one has to follow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AccountTxnID: Option<String>,
+    ///The transaction cost, it has to be set before signing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Fee: Option<Drops>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Flags: Option<u32>,
+    ///The last ledger the transaction can be included in, see [1]
+    ///1: https://xrpl.org/reliable-transaction-submission.html
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LastLedgerSequence: Option<u32>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Memos: Option<Vec<MemoWrapper>>,
+    ///The chain the transaction is for, only on chains with an ID above 1024.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkID: Option<u32>,
+    ///The sequence number of the transaction for its account, it has to be set before signing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Sequence: Option<u32>,
+    ///Set by [`crate::combine_signers`] on multi-signed transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Signers: Option<Vec<SignerWrapper>>,
+    ///Set when signing, empty on multi-signed transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SigningPubKey: Option<String>,
+    ///Identifies the hosted sender of the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SourceTag: Option<u32>,
+    ///The Ticket to use instead of a sequence number, with a `Sequence` of 0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TicketSequence: Option<u32>,
+    ///Set when signing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TxnSignature: Option<String>,
+}
+
+impl CommonFields {
+    /// The fields of a transaction sent by `account`, with every optional field unset.
+    pub fn new(account: Account) -> Self {
+        CommonFields {
+            Account: account,
+            AccountTxnID: None,
+            Fee: None,
+            Flags: None,
+            LastLedgerSequence: None,
+            Memos: None,
+            NetworkID: None,
+            Sequence: None,
+            Signers: None,
+            SigningPubKey: None,
+            SourceTag: None,
+            TicketSequence: None,
+            TxnSignature: None,
+        }
+    }
+}
+
+///Arbitrary data attached to a transaction, every field in hex.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Memo {
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoData: Option<String>,
+    ///Like a MIME type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoFormat: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoType: Option<String>,
+}
+
+///The ledger wraps every entry of a `Memos` array in a `Memo` object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MemoWrapper {
+    ///
+    pub Memo: Memo,
+}
+
+macro_rules! transactions {
+    ($($(#[$doc:meta])* $variant:ident,)*) => {
+        /// Any transaction, tagged by its `TransactionType`. The types this crate does not
+        /// know, like the pseudo-transactions of the validators, are kept as raw JSON.
+        #[derive(Debug, Clone)]
+        pub enum Transaction {
+            $($(#[$doc])* $variant($variant),)*
+            /// A transaction of a type this crate does not know about
+            Unknown(Value),
+        }
+
+        impl Transaction {
+            /// The `TransactionType`, like `Payment`.
+            pub fn transaction_type(&self) -> &str {
+                match self {
+                    $(Transaction::$variant(_) => stringify!($variant),)*
+                    Transaction::Unknown(transaction) => {
+                        transaction["TransactionType"].as_str().unwrap_or_default()
+                    }
+                }
+            }
+
+            /// The fields the transaction shares with the others, `None` for an unknown type.
+            pub fn common(&self) -> Option<&CommonFields> {
+                match self {
+                    $(Transaction::$variant(transaction) => Some(&transaction.common),)*
+                    Transaction::Unknown(_) => None,
+                }
+            }
+
+            ///
+            pub fn common_mut(&mut self) -> Option<&mut CommonFields> {
+                match self {
+                    $(Transaction::$variant(transaction) => Some(&mut transaction.common),)*
+                    Transaction::Unknown(_) => None,
+                }
+            }
+        }
+
+        // Every type already writes its own tag
+        impl Serialize for Transaction {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $(Transaction::$variant(transaction) => transaction.serialize(serializer),)*
+                    Transaction::Unknown(transaction) => transaction.serialize(serializer),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Transaction {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let mut transaction = Value::deserialize(deserializer)?;
+                // API v2 answers name the Amount of a Payment DeliverMax
+                let is_payment = transaction["TransactionType"] == "Payment";
+                if is_payment && transaction.get("Amount").is_none() {
+                    if let Some(deliver_max) = transaction.get("DeliverMax").cloned() {
+                        transaction["Amount"] = deliver_max;
+                    }
+                }
+                match transaction["TransactionType"].as_str() {
+                    $(Some(stringify!($variant)) => from_value(transaction).map(Transaction::$variant),)*
+                    Some(_) => Ok(Transaction::Unknown(transaction)),
+                    None => Err(D::Error::missing_field("TransactionType")),
+                }
+            }
+        }
+
+        $(
+            impl From<$variant> for Transaction {
+                fn from(transaction: $variant) -> Self {
+                    Transaction::$variant(transaction)
+                }
+            }
+        )*
+    };
+}
+
+fn from_value<T: DeserializeOwned, E: Error>(transaction: Value) -> Result<T, E> {
+    serde_json::from_value(transaction).map_err(E::custom)
+}
+
+transactions! {
+    ///https://xrpl.org/payment.html
+    Payment,
+    ///https://xrpl.org/offercreate.html
+    OfferCreate,
+    ///https://xrpl.org/offercancel.html
+    OfferCancel,
+    ///https://xrpl.org/trustset.html
+    TrustSet,
+    ///https://xrpl.org/accountset.html
+    AccountSet,
+    ///https://xrpl.org/escrowcreate.html
+    EscrowCreate,
+    ///https://xrpl.org/escrowfinish.html
+    EscrowFinish,
+    ///https://xrpl.org/escrowcancel.html
+    EscrowCancel,
+    ///https://xrpl.org/paymentchannelcreate.html
+    PaymentChannelCreate,
+    ///https://xrpl.org/paymentchannelfund.html
+    PaymentChannelFund,
+    ///https://xrpl.org/paymentchannelclaim.html
+    PaymentChannelClaim,
+    ///https://xrpl.org/checkcreate.html
+    CheckCreate,
+    ///https://xrpl.org/checkcash.html
+    CheckCash,
+    ///https://xrpl.org/checkcancel.html
+    CheckCancel,
+    ///https://xrpl.org/setregularkey.html
+    SetRegularKey,
+    ///https://xrpl.org/signerlistset.html
+    SignerListSet,
+    ///https://xrpl.org/accountdelete.html
+    AccountDelete,
+    ///https://xrpl.org/ticketcreate.html
+    TicketCreate,
+    ///https://xrpl.org/nftokenmint.html
+    NFTokenMint,
+    ///https://xrpl.org/nftokenburn.html
+    NFTokenBurn,
+    ///https://xrpl.org/nftokencreateoffer.html
+    NFTokenCreateOffer,
+    ///https://xrpl.org/nftokencanceloffer.html
+    NFTokenCancelOffer,
+    ///https://xrpl.org/nftokenacceptoffer.html
+    NFTokenAcceptOffer,
+    ///https://xrpl.org/ammcreate.html
+    AMMCreate,
+    ///https://xrpl.org/ammdeposit.html
+    AMMDeposit,
+    ///https://xrpl.org/ammwithdraw.html
+    AMMWithdraw,
+    ///https://xrpl.org/ammvote.html
+    AMMVote,
+    ///https://xrpl.org/ammbid.html
+    AMMBid,
+    ///https://xrpl.org/ammdelete.html
+    AMMDelete,
+}
+
+///https://xrpl.org/payment.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct Payment {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The amount to deliver, in drops of XRP or in an issued currency.
+    pub Amount: Balance,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeliverMin: Option<Balance>,
+    ///
+    pub Destination: Account,
+    ///Identifies the reason for the payment, or a hosted recipient to pay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationTag: Option<u32>,
+    ///A 256-bit hash, in hex, identifying the reason for the payment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub InvoiceID: Option<String>,
+    ///The paths for payments that convert currencies, as `ripple_path_find` gives them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Paths: Option<Vec<Vec<PathInfo>>>,
+    ///The most the sender is willing to spend, for payments that convert currencies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SendMax: Option<Balance>,
+}
+
+impl Payment {
+    /// A payment of `amount` from `account` to `destination`, with every optional field unset.
+    pub fn new(account: Account, destination: Account, amount: Balance) -> Self {
+        Payment {
+            common: CommonFields::new(account),
+            Amount: amount,
+            DeliverMin: None,
+            Destination: destination,
+            DestinationTag: None,
+            InvoiceID: None,
+            Paths: None,
+            SendMax: None,
+        }
+    }
+}
+
+///https://xrpl.org/offercreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct OfferCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///When the offer stops being active, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expiration: Option<u32>,
+    ///An offer of the account to cancel first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OfferSequence: Option<u32>,
+    ///What the account gives.
+    pub TakerGets: Balance,
+    ///What the account wants in exchange.
+    pub TakerPays: Balance,
+}
+
+///https://xrpl.org/offercancel.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct OfferCancel {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The sequence number, or Ticket, of the transaction that created the offer.
+    pub OfferSequence: u32,
+}
+
+///https://xrpl.org/trustset.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct TrustSet {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The trust line to change: its currency, its peer as the issuer, and its limit.
+    pub LimitAmount: Balance,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub QualityIn: Option<u32>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub QualityOut: Option<u32>,
+}
+
+///https://xrpl.org/accountset.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AccountSet {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///An account flag to turn off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ClearFlag: Option<u32>,
+    ///The domain of the account, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Domain: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EmailHash: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MessageKey: Option<String>,
+    ///An account allowed to mint NFTokens for this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NFTokenMinter: Option<Account>,
+    ///An account flag to turn on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SetFlag: Option<u32>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TickSize: Option<u8>,
+    ///The fee to charge on transfers of the currencies the account issues, in billionths.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TransferRate: Option<u32>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WalletLocator: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WalletSize: Option<u32>,
+}
+
+///https://xrpl.org/escrowcreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct EscrowCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The XRP to set aside.
+    pub Amount: Balance,
+    ///When the escrow expires, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CancelAfter: Option<u32>,
+    ///A PREIMAGE-SHA-256 crypto-condition, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Condition: Option<String>,
+    ///
+    pub Destination: Account,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationTag: Option<u32>,
+    ///When the escrow can be finished, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub FinishAfter: Option<u32>,
+}
+
+///https://xrpl.org/escrowfinish.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct EscrowFinish {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Condition: Option<String>,
+    ///The fulfillment of the `Condition` of the escrow, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Fulfillment: Option<String>,
+    ///The sequence number of the transaction that created the escrow.
+    pub OfferSequence: u32,
+    ///The account that created the escrow.
+    pub Owner: Account,
+}
+
+///https://xrpl.org/escrowcancel.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct EscrowCancel {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The sequence number of the transaction that created the escrow.
+    pub OfferSequence: u32,
+    ///The account that created the escrow.
+    pub Owner: Account,
+}
+
+///https://xrpl.org/paymentchannelcreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct PaymentChannelCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The XRP to set aside in the channel.
+    pub Amount: Balance,
+    ///When the channel expires, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CancelAfter: Option<u32>,
+    ///
+    pub Destination: Account,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationTag: Option<u32>,
+    ///The key that signs the claims against the channel, in hex.
+    pub PublicKey: String,
+    ///How many seconds the source has to wait to close a channel with XRP left.
+    pub SettleDelay: u32,
+}
+
+///https://xrpl.org/paymentchannelfund.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct PaymentChannelFund {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The XRP to add to the channel.
+    pub Amount: Balance,
+    ///The ID of the channel.
+    pub Channel: String,
+    ///A new expiration of the channel, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expiration: Option<u32>,
+}
+
+///https://xrpl.org/paymentchannelclaim.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct PaymentChannelClaim {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The amount of XRP the `Signature` authorizes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount: Option<Balance>,
+    ///The total amount of XRP delivered by the channel after this claim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Balance: Option<Balance>,
+    ///The ID of the channel.
+    pub Channel: String,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PublicKey: Option<String>,
+    ///The signature of the claim, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Signature: Option<String>,
+}
+
+///https://xrpl.org/checkcreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct CheckCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///
+    pub Destination: Account,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationTag: Option<u32>,
+    ///When the check expires, in seconds since the Ripple Epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expiration: Option<u32>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub InvoiceID: Option<String>,
+    ///The most the check can debit the sender, fee included.
+    pub SendMax: Balance,
+}
+
+///https://xrpl.org/checkcash.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct CheckCash {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The exact amount to receive, instead of `DeliverMin`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount: Option<Balance>,
+    ///The ID of the check.
+    pub CheckID: String,
+    ///The least amount to receive, instead of `Amount`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeliverMin: Option<Balance>,
+}
+
+///https://xrpl.org/checkcancel.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct CheckCancel {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The ID of the check.
+    pub CheckID: String,
+}
+
+///https://xrpl.org/setregularkey.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct SetRegularKey {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The account of the new regular key, unset to remove the regular key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RegularKey: Option<Account>,
+}
+
+///https://xrpl.org/signerlistset.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct SignerListSet {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The signers and their weights, unset with a quorum of 0 to remove the SignerList.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SignerEntries: Option<Vec<SignerEntryWrapper>>,
+    ///
+    pub SignerQuorum: u32,
+}
+
+///https://xrpl.org/accountdelete.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AccountDelete {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The account to send the remaining XRP to.
+    pub Destination: Account,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationTag: Option<u32>,
+}
+
+///https://xrpl.org/ticketcreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct TicketCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///How many Tickets to create, up to 250.
+    pub TicketCount: u32,
+}
+
+///https://xrpl.org/nftokenmint.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct NFTokenMint {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The issuer of the token, when minted by an authorized minter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Issuer: Option<Account>,
+    ///
+    pub NFTokenTaxon: u32,
+    ///The fee to charge on secondary sales, in hundredths of a percent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TransferFee: Option<u16>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub URI: Option<String>,
+}
+
+///https://xrpl.org/nftokenburn.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct NFTokenBurn {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///
+    pub NFTokenID: String,
+    ///The holder of the token, when the issuer burns it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Owner: Option<Account>,
+}
+
+///https://xrpl.org/nftokencreateoffer.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct NFTokenCreateOffer {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The amount asked, for a sell offer, or offered, for a buy offer.
+    pub Amount: Balance,
+    ///The only account that can accept the offer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Destination: Option<Account>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expiration: Option<u32>,
+    ///
+    pub NFTokenID: String,
+    ///The holder of the token, for a buy offer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Owner: Option<Account>,
+}
+
+///https://xrpl.org/nftokencanceloffer.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct NFTokenCancelOffer {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The IDs of the offers to cancel.
+    pub NFTokenOffers: Vec<String>,
+}
+
+///https://xrpl.org/nftokenacceptoffer.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct NFTokenAcceptOffer {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The cut of a broker matching a buy and a sell offer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NFTokenBrokerFee: Option<Balance>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NFTokenBuyOffer: Option<String>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NFTokenSellOffer: Option<String>,
+}
+
+///https://xrpl.org/ammcreate.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMCreate {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The first asset to deposit.
+    pub Amount: Balance,
+    ///The second asset to deposit.
+    pub Amount2: Balance,
+    ///The fee of the AMM, in thousandths of a percent.
+    pub TradingFee: u16,
+}
+
+///https://xrpl.org/ammdeposit.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMDeposit {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount: Option<Balance>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount2: Option<Balance>,
+    ///The first asset of the AMM.
+    pub Asset: Issue,
+    ///The second asset of the AMM.
+    pub Asset2: Issue,
+    ///The highest price to pay per LP token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EPrice: Option<Balance>,
+    ///The LP tokens to receive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LPTokenOut: Option<Balance>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TradingFee: Option<u16>,
+}
+
+///https://xrpl.org/ammwithdraw.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMWithdraw {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount: Option<Balance>,
+    ///
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Amount2: Option<Balance>,
+    ///The first asset of the AMM.
+    pub Asset: Issue,
+    ///The second asset of the AMM.
+    pub Asset2: Issue,
+    ///The lowest price to receive per LP token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EPrice: Option<Balance>,
+    ///The LP tokens to give back.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LPTokenIn: Option<Balance>,
+}
+
+///https://xrpl.org/ammvote.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMVote {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The first asset of the AMM.
+    pub Asset: Issue,
+    ///The second asset of the AMM.
+    pub Asset2: Issue,
+    ///The fee to vote for, in thousandths of a percent.
+    pub TradingFee: u16,
+}
+
+///https://xrpl.org/ammbid.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMBid {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The first asset of the AMM.
+    pub Asset: Issue,
+    ///The second asset of the AMM.
+    pub Asset2: Issue,
+    ///Up to 4 more accounts that get the discounted fee of the auction slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AuthAccounts: Option<Vec<AuthAccountWrapper>>,
+    ///The most LP tokens to pay for the auction slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BidMax: Option<Balance>,
+    ///The least LP tokens to pay for the auction slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BidMin: Option<Balance>,
+}
+
+///https://xrpl.org/ammdelete.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "TransactionType")]
+pub struct AMMDelete {
+    ///
+    #[serde(flatten)]
+    pub common: CommonFields,
+    ///The first asset of the AMM.
+    pub Asset: Issue,
+    ///The second asset of the AMM.
+    pub Asset2: Issue,
+}
+
+#[test]
+fn transaction_test() {
+    let info: crate::LedgerInfo =
+        serde_json::from_reader(std::fs::File::open("ledger.json").unwrap()).unwrap();
+    let transactions = info.ledger.unwrap().transactions.unwrap();
+    let offer = transactions
+        .iter()
+        .find(|info| {
+            info.hash == "00E9E1645D940A74883874BBAC246D95B84D12F38B394F8C31D171FFDCEBB8DF"
+        })
+        .unwrap();
+    match &offer.transaction {
+        Transaction::OfferCreate(offer) => {
+            assert_eq!(offer.OfferSequence, Some(3790853));
+            assert_eq!(offer.common.Sequence, Some(3790857));
+            match &offer.TakerPays {
+                Balance::Other { currency, .. } => assert_eq!(currency, "CNY"),
+                other => panic!("Expecting an issued amount and got {:?}", other),
+            }
+        }
+        other => panic!("Expecting an OfferCreate and got {:?}", other),
+    }
+    assert!(transactions
+        .iter()
+        .any(|info| matches!(&info.transaction, Transaction::Payment(payment) if payment.DestinationTag.is_some())));
+
+    let set_fee = serde_json::json!({
+        "Account": "rrrrrrrrrrrrrrrrrrrrrhoLvTp",
+        "BaseFee": "000000000000000A",
+        "Fee": "0",
+        "LedgerSequence": 3,
+        "ReferenceFeeUnits": 10,
+        "ReserveBase": 20000000,
+        "ReserveIncrement": 5000000,
+        "Sequence": 0,
+        "SigningPubKey": "",
+        "TransactionType": "SetFee"
+    });
+    let transaction: Transaction = serde_json::from_value(set_fee.clone()).unwrap();
+    assert_eq!(transaction.transaction_type(), "SetFee");
+    assert!(transaction.common().is_none());
+    assert_eq!(serde_json::to_value(&transaction).unwrap(), set_fee);
+
+    // API v2 answers give the Amount of a Payment as DeliverMax
+    let deliver_max = serde_json::json!({
+        "Account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+        "DeliverMax": "1000000",
+        "Destination": "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh",
+        "Fee": "12",
+        "Sequence": 5,
+        "TransactionType": "Payment"
+    });
+    let transaction: Transaction = serde_json::from_value(deliver_max).unwrap();
+    match &transaction {
+        Transaction::Payment(payment) => {
+            match &payment.Amount {
+                Balance::XRP(drops) => assert_eq!(drops.drops(), 1000000),
+                other => panic!("Expecting XRP and got {:?}", other),
+            }
+            assert_eq!(payment.common.Sequence, Some(5));
+        }
+        other => panic!("Expecting a Payment and got {:?}", other),
+    }
+    assert!(transaction.common().is_some());
+
+    // A known type that does not fit its fields is an error, not an unknown transaction
+    let no_destination = serde_json::json!({
+        "Account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+        "Amount": "1000000",
+        "TransactionType": "Payment"
+    });
+    assert!(serde_json::from_value::<Transaction>(no_destination).is_err());
+}
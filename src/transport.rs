@@ -0,0 +1,154 @@
+use crate::{XRPWebSocketClient, XrpRpcError};
+use anyhow::anyhow;
+use futures::future::BoxFuture;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Mutex;
+use throttled_json_rpc::{ClientAuth, ClientOptions, ReqBatcher, RPS};
+
+/// What [`crate::XRPClient`] sends its requests through. A transport takes a rippled method
+/// with its params object and gives back the `result` object of the answer. Answers with an
+/// error status are left to the client to sort out.
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Sends one request.
+    fn request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Value, XrpRpcError>>;
+}
+
+/// The JSON-RPC over HTTP transport, throttled and batched by a [`ReqBatcher`].
+#[derive(Clone, Debug)]
+pub struct ReqBatcherTransport {
+    request_batcher: ReqBatcher,
+}
+
+impl ReqBatcherTransport {
+    /// A transport for the node and the limits in the options.
+    pub fn new(options: ClientOptions) -> Self {
+        ReqBatcherTransport {
+            request_batcher: ReqBatcher::new(options),
+        }
+    }
+
+    /// A transport to the node at `uri`, with the passwords and limitations of the server,
+    /// like how many rps to allow. The rps may not be valid, like negative, so it could fail.
+    pub fn connect(
+        uri: String,
+        user: Option<String>,
+        pass: Option<String>,
+        max_concurrency: usize,
+        rps: f64,
+        max_batch_size: usize,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::new(ClientOptions {
+            uri,
+            batching: max_batch_size,
+            rps: RPS::new(rps)?,
+            concurrent: max_concurrency,
+            client_auth: user.map(|user| ClientAuth {
+                user,
+                password: pass,
+            }),
+        }))
+    }
+}
+
+impl Transport for ReqBatcherTransport {
+    fn request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Value, XrpRpcError>> {
+        Box::pin(async move {
+            self.request_batcher
+                .request(method.to_string(), vec![params])
+                .await
+                .map_err(XrpRpcError::from_transport)
+        })
+    }
+}
+
+impl Transport for XRPWebSocketClient {
+    fn request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Value, XrpRpcError>> {
+        Box::pin(async move { XRPWebSocketClient::request(self, method, &params).await })
+    }
+}
+
+/// A transport for tests, answering from a script of canned results instead of a node and
+/// recording every request it gets.
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<(String, Result<Value, XrpRpcError>)>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockTransport {
+    /// A transport with an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the result to give to the next request, which has to be for `method`. A result
+    /// with an error status, like `{"error": "actNotFound", "status": "error"}`, makes the
+    /// client fail the way it would with a real node.
+    pub fn push_response(&self, method: &str, result: Value) -> &Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back((method.to_string(), Ok(result)));
+        self
+    }
+
+    /// Queues a failure of the transport itself for the next request, which has to be for
+    /// `method`.
+    pub fn push_error(&self, method: &str, error: XrpRpcError) -> &Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back((method.to_string(), Err(error)));
+        self
+    }
+
+    /// The requests received so far, oldest first, as method and params.
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// How many of the queued results have not been asked for yet.
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+}
+
+impl Transport for MockTransport {
+    fn request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Value, XrpRpcError>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push((method.to_string(), params));
+        let response = match self.responses.lock().unwrap().pop_front() {
+            Some((expected, result)) if expected == method => result,
+            Some((expected, _)) => Err(XrpRpcError::Transport(anyhow!(
+                "MockTransport expected a {} request and got {}",
+                expected,
+                method
+            ))),
+            None => Err(XrpRpcError::Transport(anyhow!(
+                "MockTransport has no response left for {}",
+                method
+            ))),
+        };
+        Box::pin(async move { response })
+    }
+}
@@ -0,0 +1,410 @@
+use crate::{Account, Drops, Issue, MetaTxInfo, TransactionInfo, XrpRpcError};
+use anyhow::{anyhow, Result};
+use futures::channel::{mpsc, oneshot};
+use futures::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+
+/// A client talking to rippled over a WebSocket, which is the only way to use `subscribe`.
+/// Requests take the same params and give back the same responses as [`crate::XRPClient`].
+/// Cloning it shares the connection.
+#[derive(Clone, Debug)]
+pub struct XRPWebSocketClient {
+    outgoing: mpsc::UnboundedSender<Message>,
+    responders: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// The kinds of subscription, rippled sends `transaction` messages for all but the ledger one.
+#[derive(Debug, Clone)]
+enum Feed {
+    Ledger,
+    Transactions,
+    Accounts(Vec<Account>),
+    Books(Vec<Book>),
+}
+
+/// A stream given by one of the `subscribe_*` methods, and what it follows.
+#[derive(Debug)]
+struct Subscriber {
+    feed: Feed,
+    sender: mpsc::UnboundedSender<Value>,
+}
+
+impl Feed {
+    /// Whether the stream gets the message. rippled sends a transaction once per connection,
+    /// whatever the number of subscriptions it matches, so the accounts and books it touches
+    /// are looked for in its `transaction` and `meta`.
+    fn wants(&self, message: &Value) -> bool {
+        match (self, message["type"].as_str()) {
+            (Feed::Ledger, Some("ledgerClosed")) => true,
+            (Feed::Transactions, Some("transaction")) => true,
+            (Feed::Accounts(accounts), Some("transaction")) => accounts.iter().any(|account| {
+                let address = account.to_string();
+                mentions(&message["transaction"], &address) || mentions(&message["meta"], &address)
+            }),
+            (Feed::Books(books), Some("transaction")) => offer_issues(&message["meta"])
+                .iter()
+                .any(|(gets, pays)| books.iter().any(|book| book.has(gets, pays))),
+            _ => false,
+        }
+    }
+
+    /// Takes out the accounts or books of `removed`, false when nothing is left to follow.
+    fn remove(&mut self, removed: &Feed) -> bool {
+        match (self, removed) {
+            (Feed::Ledger, Feed::Ledger) | (Feed::Transactions, Feed::Transactions) => false,
+            (Feed::Accounts(accounts), Feed::Accounts(removed)) => {
+                accounts.retain(|account| !removed.contains(account));
+                !accounts.is_empty()
+            }
+            (Feed::Books(books), Feed::Books(removed)) => {
+                books.retain(|book| !removed.iter().any(|other| book.same_as(other)));
+                !books.is_empty()
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Whether `address` is any of the strings in `value`: a field of the transaction, or of a
+/// ledger object it changed, like `Owner` or the issuer of an amount.
+fn mentions(value: &Value, address: &str) -> bool {
+    match value {
+        Value::String(string) => string == address,
+        Value::Array(values) => values.iter().any(|value| mentions(value, address)),
+        Value::Object(fields) => fields.values().any(|value| mentions(value, address)),
+        _ => false,
+    }
+}
+
+/// The `TakerGets` and `TakerPays` of the offers created, changed or deleted, from `meta`.
+fn offer_issues(meta: &Value) -> Vec<(Issue, Issue)> {
+    meta["AffectedNodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.as_object()?.values().next())
+        .filter(|node| node["LedgerEntryType"] == "Offer")
+        .filter_map(|node| {
+            let fields = match node.get("FinalFields") {
+                Some(fields) => fields,
+                None => node.get("NewFields")?,
+            };
+            Some((issue(&fields["TakerGets"])?, issue(&fields["TakerPays"])?))
+        })
+        .collect()
+}
+
+/// The currency of an amount, XRP for drops.
+fn issue(amount: &Value) -> Option<Issue> {
+    match amount {
+        Value::String(_) => Some(Issue {
+            currency: "XRP".into(),
+            issuer: None,
+        }),
+        Value::Object(fields) => Some(Issue {
+            currency: fields.get("currency")?.as_str()?.into(),
+            issuer: fields.get("issuer").and_then(Value::as_str).map(Into::into),
+        }),
+        _ => None,
+    }
+}
+
+/// An order book to follow with [`XRPWebSocketClient::subscribe_books`]. See [1]
+/// 1: https://xrpl.org/subscribe.html#order-book-streams
+#[derive(Serialize, Debug, Clone)]
+pub struct Book {
+    ///The currency the offers of the book give
+    pub taker_gets: Issue,
+    ///The currency the offers of the book ask for
+    pub taker_pays: Issue,
+    ///The account to use as a perspective for the funding of the offers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taker: Option<Account>,
+    ///If true, also follow the offers in the other direction of the book
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub both: Option<bool>,
+}
+
+impl Book {
+    /// Whether offers giving `gets` for `pays` are in the book.
+    fn has(&self, gets: &Issue, pays: &Issue) -> bool {
+        (self.taker_gets == *gets && self.taker_pays == *pays)
+            || (self.both == Some(true) && self.taker_gets == *pays && self.taker_pays == *gets)
+    }
+
+    fn same_as(&self, other: &Book) -> bool {
+        self.taker_gets == other.taker_gets && self.taker_pays == other.taker_pays
+    }
+}
+
+/// The message of the ledger stream, sent every time the consensus process declares a new
+/// validated ledger. See [1]
+/// 1: https://xrpl.org/subscribe.html#ledger-stream
+#[derive(Deserialize, Debug)]
+pub struct LedgerClosed {
+    ///The reference transaction cost as of this ledger version, in drops of XRP.
+    pub fee_base: Drops,
+    ///
+    pub fee_ref: Option<u64>,
+    ///
+    pub ledger_hash: String,
+    ///
+    pub ledger_index: u64,
+    ///The time this ledger was closed, in seconds since the Ripple Epoch
+    pub ledger_time: u64,
+    ///The minimum reserve, in drops of XRP, that is required for an account.
+    pub reserve_base: Drops,
+    ///The owner reserve for each object an account owns in the ledger, in drops of XRP.
+    pub reserve_inc: Drops,
+    ///Number of new transactions included in this ledger version.
+    pub txn_count: Option<u64>,
+    ///Range of ledgers that the server has available, like "32570-48293247".
+    pub validated_ledgers: Option<String>,
+}
+
+/// The message of the transaction, account and book streams. See [1]
+/// 1: https://xrpl.org/subscribe.html#transaction-streams
+#[derive(Deserialize, Debug)]
+pub struct TransactionMessage {
+    ///
+    pub engine_result: String,
+    ///
+    pub engine_result_code: i64,
+    ///
+    pub engine_result_message: String,
+    ///
+    pub ledger_hash: Option<String>,
+    ///
+    pub ledger_index: Option<u64>,
+    ///Only in the streams of proposed, not yet validated, transactions
+    pub ledger_current_index: Option<u64>,
+    ///
+    pub meta: Option<MetaTxInfo>,
+    ///
+    pub transaction: TransactionInfo,
+    ///
+    pub validated: bool,
+}
+
+impl XRPWebSocketClient {
+    /// Opens the connection, like `wss://s1.ripple.com/`. The connection is served by
+    /// background tasks, so this has to run inside a tokio runtime.
+    pub async fn connect(url: &str) -> Result<Self, XrpRpcError> {
+        let (socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|error| XrpRpcError::Transport(error.into()))?;
+        let (write, mut read) = socket.split();
+        let (outgoing, outgoing_receiver) = mpsc::unbounded();
+        let client = XRPWebSocketClient {
+            outgoing,
+            responders: Default::default(),
+            subscribers: Default::default(),
+            next_id: Arc::new(AtomicU64::new(1)),
+        };
+        tokio::spawn(outgoing_receiver.map(Ok).forward(write));
+        let responders = client.responders.clone();
+        let subscribers = client.subscribers.clone();
+        tokio::spawn(async move {
+            while let Some(message) = read.next().await {
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(_) => continue,
+                    Err(_) => break,
+                };
+                let value: Value = match serde_json::from_str(&text) {
+                    Ok(value) => value,
+                    Err(_) => continue,
+                };
+                match value.get("type").and_then(Value::as_str) {
+                    Some("response") => {
+                        let responder = value
+                            .get("id")
+                            .and_then(Value::as_u64)
+                            .and_then(|id| responders.lock().unwrap().remove(&id));
+                        if let Some(responder) = responder {
+                            let _ = responder.send(value);
+                        }
+                    }
+                    Some("ledgerClosed") | Some("transaction") => dispatch(&subscribers, &value),
+                    _ => {}
+                }
+            }
+            // Dropping the senders fails the pending requests and ends the streams
+            responders.lock().unwrap().clear();
+            subscribers.lock().unwrap().clear();
+        });
+        Ok(client)
+    }
+
+    /// Sends any command, the params being one of the params structs of the crate, like
+    /// [`crate::AccountInfoParams`], and gives back the `result` of the answer.
+    pub async fn request<P: Serialize, T: DeserializeOwned>(
+        &self,
+        command: &str,
+        params: &P,
+    ) -> Result<T, XrpRpcError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut request = match serde_json::to_value(params).map_err(XrpRpcError::Encode)? {
+            Value::Object(request) => request,
+            Value::Null => Map::new(),
+            other => {
+                return Err(XrpRpcError::Transport(anyhow!(
+                    "The params of {} are not an object: {}",
+                    command,
+                    other
+                )))
+            }
+        };
+        request.insert("id".into(), id.into());
+        request.insert("command".into(), command.into());
+        let (responder, response) = oneshot::channel();
+        self.responders.lock().unwrap().insert(id, responder);
+        if self
+            .outgoing
+            .unbounded_send(Message::Text(Value::Object(request).to_string()))
+            .is_err()
+        {
+            self.responders.lock().unwrap().remove(&id);
+            return Err(XrpRpcError::Transport(anyhow!(
+                "The websocket connection is closed"
+            )));
+        }
+        let mut response = response.await.map_err(|_| {
+            XrpRpcError::Transport(anyhow!(
+                "The websocket connection closed before {} was answered",
+                command
+            ))
+        })?;
+        if let Some(error) = XrpRpcError::from_response(&response) {
+            return Err(error);
+        }
+        let result = response
+            .get_mut("result")
+            .map(Value::take)
+            .unwrap_or(Value::Null);
+        serde_json::from_value(result.clone()).map_err(|error| XrpRpcError::decode(error, &result))
+    }
+
+    /// Follows the validated ledgers as they close.
+    pub async fn subscribe_ledger(
+        &self,
+    ) -> Result<impl Stream<Item = Result<LedgerClosed, XrpRpcError>>, XrpRpcError> {
+        self.subscribe(Feed::Ledger, json!({ "streams": ["ledger"] }))
+            .await
+    }
+
+    /// Follows every validated transaction of the network.
+    pub async fn subscribe_transactions(
+        &self,
+    ) -> Result<impl Stream<Item = Result<TransactionMessage, XrpRpcError>>, XrpRpcError> {
+        self.subscribe(Feed::Transactions, json!({ "streams": ["transactions"] }))
+            .await
+    }
+
+    /// Follows the validated transactions that affect any of the accounts.
+    pub async fn subscribe_accounts(
+        &self,
+        accounts: &[Account],
+    ) -> Result<impl Stream<Item = Result<TransactionMessage, XrpRpcError>>, XrpRpcError> {
+        self.subscribe(
+            Feed::Accounts(accounts.to_vec()),
+            json!({ "accounts": accounts }),
+        )
+        .await
+    }
+
+    /// Follows the validated transactions that affect any of the order books.
+    pub async fn subscribe_books(
+        &self,
+        books: &[Book],
+    ) -> Result<impl Stream<Item = Result<TransactionMessage, XrpRpcError>>, XrpRpcError> {
+        self.subscribe(Feed::Books(books.to_vec()), json!({ "books": books }))
+            .await
+    }
+
+    /// Stops the ledger stream, ending the streams given by `subscribe_ledger`.
+    pub async fn unsubscribe_ledger(&self) -> Result<(), XrpRpcError> {
+        self.unsubscribe(Feed::Ledger, json!({ "streams": ["ledger"] }))
+            .await
+    }
+
+    /// Stops the transaction stream, ending the streams given by `subscribe_transactions`.
+    pub async fn unsubscribe_transactions(&self) -> Result<(), XrpRpcError> {
+        self.unsubscribe(Feed::Transactions, json!({ "streams": ["transactions"] }))
+            .await
+    }
+
+    /// Stops following the accounts. The streams given by `subscribe_accounts` stop getting
+    /// their transactions, and end once none of their accounts is followed.
+    pub async fn unsubscribe_accounts(&self, accounts: &[Account]) -> Result<(), XrpRpcError> {
+        self.unsubscribe(
+            Feed::Accounts(accounts.to_vec()),
+            json!({ "accounts": accounts }),
+        )
+        .await
+    }
+
+    /// Stops following the books. The streams given by `subscribe_books` stop getting their
+    /// transactions, and end once none of their books is followed.
+    pub async fn unsubscribe_books(&self, books: &[Book]) -> Result<(), XrpRpcError> {
+        self.unsubscribe(Feed::Books(books.to_vec()), json!({ "books": books }))
+            .await
+    }
+
+    /// Transaction messages do not say which subscription they are for, so each stream gets
+    /// the ones that touch its accounts or books.
+    async fn subscribe<T: DeserializeOwned>(
+        &self,
+        feed: Feed,
+        params: Value,
+    ) -> Result<impl Stream<Item = Result<T, XrpRpcError>>, XrpRpcError> {
+        let (sender, receiver) = mpsc::unbounded();
+        // Registered before asking, so that no message sent right after the answer is lost
+        self.subscribers.lock().unwrap().push(Subscriber {
+            feed,
+            sender: sender.clone(),
+        });
+        if let Err(error) = self.request::<_, Value>("subscribe", &params).await {
+            self.subscribers
+                .lock()
+                .unwrap()
+                .retain(|subscriber| !subscriber.sender.same_receiver(&sender));
+            return Err(error);
+        }
+        Ok(receiver.map(|value: Value| {
+            serde_json::from_value(value.clone())
+                .map_err(|error| XrpRpcError::decode(error, &value))
+        }))
+    }
+
+    async fn unsubscribe(&self, feed: Feed, params: Value) -> Result<(), XrpRpcError> {
+        self.request::<_, Value>("unsubscribe", &params).await?;
+        let mut subscribers = self.subscribers.lock().unwrap();
+        // rippled does not count subscriptions, so every stream of the removed ones stops
+        *subscribers = subscribers
+            .drain(..)
+            .filter_map(|mut subscriber| {
+                if subscriber.feed.remove(&feed) {
+                    Some(subscriber)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+fn dispatch(subscribers: &Mutex<Vec<Subscriber>>, value: &Value) {
+    subscribers.lock().unwrap().retain(|subscriber| {
+        !subscriber.feed.wants(value) || subscriber.sender.unbounded_send(value.clone()).is_ok()
+    });
+}
@@ -117,6 +117,7 @@ async fn account_tx_test() {
         ledger_index_max: Some(-1),
         ledger_index_min: Some(-1),
         limit: Some(2),
+        marker: None,
     };
     let raw_response = client
         .post(&URL.clone())
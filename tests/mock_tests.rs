@@ -0,0 +1,915 @@
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use throttled_xrp_rpc::{
+    combine_signers, multisign_transaction, sign_transaction, to_binary, transaction_hash, Account,
+    AccountInfoParams, AccountSequence, AccountTxParams, Balance, Checkpoint, CheckpointStore,
+    CheckpointedScanner, Drops, EngineResult, FeeUrgency, KeyType, LedgerIndex, LedgerInfoParams,
+    LedgerScanner, MemoryCheckpointStore, MockTransport, NodePool, Payment, ReliableSubmitter,
+    RetryPolicy, RippledErrorCode, ScanError, SequenceManager, SubmitOutcome, TransactionInfo,
+    TxResult, Wallet, XRPClient, XrpRpcError,
+};
+
+fn mock_client() -> (Arc<MockTransport>, XRPClient) {
+    let transport = Arc::new(MockTransport::new());
+    (transport.clone(), XRPClient::from_transport(transport))
+}
+
+fn validated_ledger(ledger_index: u64) -> Value {
+    json!({
+        "ledger_hash": format!("{:064X}", ledger_index),
+        "ledger_index": ledger_index,
+        "status": "success",
+        "validated": true
+    })
+}
+
+fn ledger(ledger_index: u64, parent_index: u64) -> Value {
+    json!({
+        "ledger": {
+            "accepted": true,
+            "account_hash": "CFA12FBAFC585D54858874ADACB1003CB4218B010CF5F8AB4C4984B194E95B4B",
+            "close_flags": 0,
+            "close_time": 620860251,
+            "close_time_human": "2019-Sep-03 21:10:51.000000000",
+            "close_time_resolution": 10,
+            "closed": true,
+            "hash": format!("{:064X}", ledger_index),
+            "ledger_hash": format!("{:064X}", ledger_index),
+            "ledger_index": ledger_index.to_string(),
+            "parent_close_time": 620860250,
+            "parent_hash": format!("{:064X}", parent_index),
+            "seqNum": ledger_index.to_string(),
+            "totalCoins": "99999999522468910",
+            "total_coins": "99999999522468910",
+            "transaction_hash": "55771C3FB148C470D36B4AE4F91D402F60C39920649C9D2C3E1829104E82654F",
+            "transactions": []
+        },
+        "ledger_hash": format!("{:064X}", ledger_index),
+        "ledger_index": ledger_index,
+        "status": "success",
+        "validated": true
+    })
+}
+
+#[tokio::test]
+async fn mock_account_info_test() {
+    let (transport, client) = mock_client();
+    transport.push_response(
+        "account_info",
+        json!({
+            "account_data": {
+                "Account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+                "Balance": "1000000000",
+                "Flags": 0,
+                "LedgerEntryType": "AccountRoot",
+                "OwnerCount": 0,
+                "PreviousTxnID": "4294BEBE5B569A18C0A2702387C9B1E7146DC3A5850C1E87204951C6FDAA4C42",
+                "PreviousTxnLgrSeq": 3,
+                "Sequence": 6,
+                "index": "92FA6A9FC8EA6018D5D16532D7795C91BFB0831355BDFDA177E86C8BF997985F"
+            },
+            "ledger_current_index": 4,
+            "status": "success",
+            "validated": false
+        }),
+    );
+    let account: Account = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3".parse().unwrap();
+    let info = client
+        .account_info(&AccountInfoParams {
+            account: &account,
+            strict: true,
+            ledger_index: LedgerIndex::StrValue {
+                ledger_index: "current".into(),
+            },
+            queue: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(info.account_data.unwrap().Balance.whole_xrp(), 1000);
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, "account_info");
+    assert_eq!(
+        requests[0].1["account"],
+        "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3"
+    );
+    assert_eq!(requests[0].1["ledger_index"], "current");
+}
+
+#[tokio::test]
+async fn mock_account_tx_stream_test() {
+    let (transport, client) = mock_client();
+    let page = |ledgers: &[u64], marker: Option<Value>| {
+        json!({
+            "account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+            "ledger_index_min": 100,
+            "ledger_index_max": 200,
+            "limit": 2,
+            "marker": marker,
+            "transactions": ledgers
+                .iter()
+                .map(|ledger_index| json!({
+                    "meta": {},
+                    "tx": { "ledger_index": ledger_index },
+                    "validated": true
+                }))
+                .collect::<Vec<_>>()
+        })
+    };
+    transport
+        .push_response(
+            "account_tx",
+            page(&[101, 102], Some(json!({ "ledger": 102, "seq": 0 }))),
+        )
+        .push_response("account_tx", page(&[150], None));
+    let account: Account = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3".parse().unwrap();
+    let transactions: Vec<_> = client
+        .account_tx_stream(AccountTxParams {
+            account: &account,
+            ledger_index_min: Some(100),
+            ledger_index_max: Some(200),
+            ledger_hash: None,
+            ledger_index: None,
+            binary: Some(false),
+            forward: Some(true),
+            limit: Some(2),
+            marker: None,
+        })
+        .collect()
+        .await;
+    let ledgers: Vec<_> = transactions
+        .into_iter()
+        .map(|transaction| transaction.unwrap().tx.ledger_index.index().unwrap())
+        .collect();
+    assert_eq!(ledgers, vec![101, 102, 150]);
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].1.get("marker").is_none());
+    assert_eq!(requests[1].1["marker"], json!({ "ledger": 102, "seq": 0 }));
+    assert_eq!(requests[1].1["forward"], true);
+}
+
+#[tokio::test]
+async fn mock_scanner_test() {
+    let (transport, client) = mock_client();
+    transport
+        .push_response("ledger", validated_ledger(11))
+        .push_response("ledger", ledger(10, 9))
+        .push_response("ledger", ledger(11, 10))
+        .push_response("ledger", validated_ledger(12))
+        .push_response("ledger", ledger(12, 7));
+    let mut scanner = LedgerScanner::new(client, 10);
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 10)
+    );
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 11)
+    );
+    match scanner.next_ledger().await.unwrap_err() {
+        ScanError::ChainBreak(chain_break) => assert_eq!(chain_break.ledger_index, 12),
+        other => panic!("Expecting a ChainBreak and got {:?}", other),
+    }
+    assert_eq!(scanner.next_index(), 12);
+    assert_eq!(transport.remaining(), 0);
+}
+
+#[tokio::test]
+async fn mock_checkpointed_scanner_test() {
+    let (transport, client) = mock_client();
+    transport
+        .push_response("ledger", validated_ledger(11))
+        .push_response("ledger", ledger(10, 9))
+        .push_response("ledger", ledger(11, 10));
+    let mut scanner =
+        CheckpointedScanner::resume(client.clone(), MemoryCheckpointStore::new(), 10).unwrap();
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 10)
+    );
+    // Without an ack the same ledger comes back, without asking the node again
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 10)
+    );
+    assert_eq!(transport.remaining(), 1);
+    assert_eq!(scanner.checkpoint().unwrap(), None);
+    let checkpoint = scanner.ack().unwrap();
+    assert_eq!(
+        checkpoint,
+        Checkpoint {
+            ledger_index: 10,
+            hash: format!("{:064X}", 10),
+        }
+    );
+    assert!(scanner.ack().is_err());
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 11)
+    );
+    assert_eq!(transport.remaining(), 0);
+
+    // A restart picks the scan up after the saved checkpoint, checking the chain from there
+    let mut store = MemoryCheckpointStore::new();
+    store.save(&checkpoint).unwrap();
+    transport
+        .push_response("ledger", validated_ledger(12))
+        .push_response("ledger", ledger(11, 10));
+    let mut scanner = CheckpointedScanner::resume(client, store, 0).unwrap();
+    assert_eq!(
+        scanner.next_ledger().await.unwrap().hash,
+        format!("{:064X}", 11)
+    );
+    let (_, params) = transport.requests().pop().unwrap();
+    assert_eq!(params["ledger_index"], 11);
+    assert_eq!(scanner.ack().unwrap().ledger_index, 11);
+    assert_eq!(scanner.checkpoint().unwrap().unwrap().ledger_index, 11);
+}
+
+#[tokio::test]
+async fn mock_rippled_error_test() {
+    let (transport, client) = mock_client();
+    transport
+        .push_response(
+            "account_info",
+            json!({
+                "account": "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3",
+                "error": "actNotFound",
+                "error_code": 19,
+                "error_message": "Account not found.",
+                "ledger_current_index": 4,
+                "status": "error",
+                "validated": false
+            }),
+        )
+        .push_response(
+            "tx",
+            json!({
+                "error": "txnNotFound",
+                "error_code": 29,
+                "error_message": "Transaction not found.",
+                "searched_all": true,
+                "status": "error"
+            }),
+        )
+        .push_response("ledger", json!({ "status": "success" }));
+    let account: Account = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3".parse().unwrap();
+    let error = client
+        .account_info(&AccountInfoParams {
+            account: &account,
+            strict: true,
+            ledger_index: LedgerIndex::StrValue {
+                ledger_index: "validated".into(),
+            },
+            queue: false,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), Some(&RippledErrorCode::ActNotFound));
+
+    match client
+        .tx(
+            "E08D6E9754025BA2534A78707605E0601F03ACE063687A0CA1BDDACFCD1698C7",
+            false,
+            Some(10),
+            Some(20),
+        )
+        .await
+        .unwrap()
+    {
+        TxResult::NotFound { searched_all } => assert!(searched_all),
+        found => panic!("Expecting the transaction not to be found, got {:?}", found),
+    }
+
+    match client
+        .ledger(&LedgerInfoParams {
+            ledger_hash: None,
+            ledger_index: None,
+            full: None,
+            accounts: None,
+            transactions: None,
+            expand: None,
+            owner_funds: None,
+            binary: None,
+            queue: None,
+        })
+        .await
+        .unwrap_err()
+    {
+        XrpRpcError::Decode { body, .. } => assert_eq!(body, r#"{"status":"success"}"#),
+        other => panic!("Expecting a decode error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn mock_retry_test() {
+    let (transport, client) = mock_client();
+    let client = client.with_retry_policy(RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..RetryPolicy::default()
+    });
+    transport
+        .push_response("ledger", json!({ "error": "slowDown", "status": "error" }))
+        .push_error("ledger", XrpRpcError::Http { status: 503 })
+        .push_response("ledger", validated_ledger(12))
+        .push_response(
+            "ledger",
+            json!({ "error": "lgrNotFound", "status": "error" }),
+        );
+    let params = LedgerInfoParams {
+        ledger_hash: None,
+        ledger_index: Some(LedgerIndex::StrValue {
+            ledger_index: "validated".into(),
+        }),
+        full: None,
+        accounts: None,
+        transactions: None,
+        expand: None,
+        owner_funds: None,
+        binary: None,
+        queue: None,
+    };
+    let ledger = client.ledger(&params).await.unwrap();
+    assert_eq!(ledger.ledger_index.index(), Some(12));
+    assert_eq!(transport.requests().len(), 3);
+
+    let error = client.ledger(&params).await.unwrap_err();
+    assert_eq!(error.code(), Some(&RippledErrorCode::LgrNotFound));
+    assert_eq!(transport.requests().len(), 4);
+}
+
+fn server_info(server_state: &str, validated_ledger: u64, complete_ledgers: &str) -> Value {
+    json!({
+        "info": {
+            "build_version": "1.12.0",
+            "complete_ledgers": complete_ledgers,
+            "load_factor": 1,
+            "server_state": server_state,
+            "validated_ledger": {
+                "age": 2,
+                "base_fee_xrp": 0.00001,
+                "hash": format!("{:064X}", validated_ledger),
+                "reserve_base_xrp": 10,
+                "reserve_inc_xrp": 2,
+                "seq": validated_ledger
+            }
+        },
+        "status": "success"
+    })
+}
+
+#[tokio::test]
+async fn mock_pool_failover_test() {
+    let first = Arc::new(MockTransport::new());
+    let second = Arc::new(MockTransport::new());
+    let pool = Arc::new(
+        NodePool::new()
+            .add_shared_node("first", first.clone())
+            .add_shared_node("second", second.clone()),
+    );
+    let client = XRPClient::from_transport(pool.clone());
+    first.push_error(
+        "ledger",
+        XrpRpcError::Transport(anyhow::anyhow!("connection refused")),
+    );
+    second
+        .push_response("ledger", validated_ledger(12))
+        .push_response("ledger", validated_ledger(13));
+    let params = LedgerInfoParams {
+        ledger_hash: None,
+        ledger_index: Some(LedgerIndex::StrValue {
+            ledger_index: "validated".into(),
+        }),
+        full: None,
+        accounts: None,
+        transactions: None,
+        expand: None,
+        owner_funds: None,
+        binary: None,
+        queue: None,
+    };
+    let ledger = client.ledger(&params).await.unwrap();
+    assert_eq!(ledger.ledger_index.index(), Some(12));
+    let health = pool.health();
+    assert!(!health[0].1.healthy);
+    assert!(health[1].1.healthy);
+
+    // The failed node is skipped until a check finds it healthy again
+    let ledger = client.ledger(&params).await.unwrap();
+    assert_eq!(ledger.ledger_index.index(), Some(13));
+    assert_eq!(first.requests().len(), 1);
+    assert_eq!(second.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn mock_pool_health_check_test() {
+    let synced = Arc::new(MockTransport::new());
+    let lagging = Arc::new(MockTransport::new());
+    let syncing = Arc::new(MockTransport::new());
+    let pool = NodePool::new()
+        .with_max_lag(5)
+        .add_shared_node("synced", synced.clone())
+        .add_shared_node("lagging", lagging.clone())
+        .add_shared_node("syncing", syncing.clone());
+    synced.push_response("server_info", server_info("full", 100, "32570-100"));
+    lagging.push_response("server_info", server_info("full", 90, "32570-90"));
+    syncing.push_response("server_info", server_info("syncing", 100, "32570-100"));
+    pool.check_health().await;
+    let health = pool.health();
+    assert!(health[0].1.healthy);
+    assert_eq!(health[0].1.validated_ledger, Some(100));
+    assert!(!health[1].1.healthy);
+    assert!(!health[2].1.healthy);
+
+    synced.push_response("ledger", validated_ledger(100));
+    let client = XRPClient::with_transport(pool);
+    let ledger = client
+        .ledger(&LedgerInfoParams {
+            ledger_hash: None,
+            ledger_index: Some(LedgerIndex::StrValue {
+                ledger_index: "validated".into(),
+            }),
+            full: None,
+            accounts: None,
+            transactions: None,
+            expand: None,
+            owner_funds: None,
+            binary: None,
+            queue: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(ledger.ledger_index.index(), Some(100));
+    assert_eq!(synced.remaining(), 0);
+}
+
+#[tokio::test]
+async fn mock_pool_history_routing_test() {
+    let pruned = Arc::new(MockTransport::new());
+    let unknown = Arc::new(MockTransport::new());
+    let archive = Arc::new(MockTransport::new());
+    let pool = NodePool::new()
+        .add_shared_node("pruned", pruned.clone())
+        .add_shared_node("unknown", unknown.clone())
+        .add_shared_node("archive", archive.clone())
+        .with_full_history_node("archive");
+    pruned.push_response("server_info", server_info("full", 100, "90-100"));
+    unknown.push_response("server_info", server_info("full", 100, "95-100"));
+    archive.push_response("server_info", server_info("full", 100, "32570-100"));
+    pool.check_health().await;
+    let client = XRPClient::with_transport(pool);
+    let ledger_params = |ledger_index: u64| LedgerInfoParams {
+        ledger_hash: None,
+        ledger_index: Some(LedgerIndex::Number {
+            ledger_index: ledger_index.into(),
+        }),
+        full: None,
+        accounts: None,
+        transactions: Some(true),
+        expand: Some(true),
+        owner_funds: None,
+        binary: None,
+        queue: None,
+    };
+
+    archive.push_response("ledger", ledger(50, 49));
+    let response = client.ledger(&ledger_params(50)).await.unwrap();
+    assert_eq!(response.ledger_index.index(), Some(50));
+    pruned.push_response("ledger", ledger(92, 91));
+    let response = client.ledger(&ledger_params(92)).await.unwrap();
+    assert_eq!(response.ledger_index.index(), Some(92));
+
+    // A node that lost the ledger since the check is passed over
+    pruned.push_response(
+        "ledger",
+        json!({ "error": "lgrNotFound", "status": "error" }),
+    );
+    archive.push_response("ledger", ledger(91, 90));
+    let response = client.ledger(&ledger_params(91)).await.unwrap();
+    assert_eq!(response.ledger_index.index(), Some(91));
+    assert_eq!(unknown.requests().len(), 1);
+    assert_eq!(pruned.remaining() + archive.remaining(), 0);
+}
+
+#[tokio::test]
+async fn mock_multisign_test() {
+    let (transport, client) = mock_client();
+    let treasury: Account = "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf".parse().unwrap();
+    let first = Wallet::generate(KeyType::Secp256k1);
+    let second = Wallet::generate(KeyType::Ed25519);
+    transport.push_response(
+        "account_objects",
+        json!({
+            "account": treasury,
+            "account_objects": [{
+                "Flags": 0,
+                "LedgerEntryType": "SignerList",
+                "OwnerNode": "0000000000000000",
+                "PreviousTxnID": "5904C0DC72C58A83AEFED2FFC5386356AA83FCA6A88C89D00646E51E687CDBE4",
+                "PreviousTxnLgrSeq": 16061435,
+                "SignerEntries": [
+                    { "SignerEntry": { "Account": first.account(), "SignerWeight": 2 } },
+                    { "SignerEntry": { "Account": second.account(), "SignerWeight": 1 } }
+                ],
+                "SignerListID": 0,
+                "SignerQuorum": 3,
+                "index": "A9C28A28B85CD533217F5C0A0C7767666B093FA58A0F2D80026FCC4CD932DDC7"
+            }],
+            "ledger_index": 16061436,
+            "validated": true
+        }),
+    );
+    let signer_list = client.signer_list(&treasury).await.unwrap().unwrap();
+    assert_eq!(signer_list.SignerQuorum, 3);
+
+    let mut payment = Payment::new(
+        treasury.clone(),
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap(),
+        Balance::XRP(Drops::from_xrp(25).unwrap()),
+    );
+    payment.common.Fee = Some(Drops::from_drops(36).unwrap());
+    payment.common.Sequence = Some(7);
+    let signers: Vec<_> = [&first, &second]
+        .iter()
+        .map(|wallet| multisign_transaction(&payment, &wallet.account(), wallet.keypair()).unwrap())
+        .collect();
+    assert!(signer_list.check_quorum(&signers[..1]).is_err());
+    let multisigned = combine_signers(&payment, &signers).unwrap();
+    signer_list.check_quorum(&multisigned.signers).unwrap();
+
+    transport.push_response(
+        "submit_multisigned",
+        json!({
+            "engine_result": "tesSUCCESS",
+            "engine_result_code": 0,
+            "engine_result_message": "The transaction was applied. Only final in a validated ledger.",
+            "tx_blob": multisigned.tx_blob,
+            "tx_json": multisigned.tx_json
+        }),
+    );
+    let submit = client.submit_multisigned(&multisigned).await.unwrap();
+    assert_eq!(submit.engine_result, EngineResult::TesSuccess);
+    let (method, params) = transport.requests().pop().unwrap();
+    assert_eq!(method, "submit_multisigned");
+    assert_eq!(params["tx_json"]["Signers"].as_array().unwrap().len(), 2);
+    assert_eq!(params["tx_json"]["SigningPubKey"], "");
+}
+
+fn submit_response(result: &str, tx_blob: &str) -> Value {
+    json!({
+        "engine_result": result,
+        "engine_result_code": 0,
+        "engine_result_message": "",
+        "tx_blob": tx_blob,
+        "tx_json": {}
+    })
+}
+
+fn tx_not_found(searched_all: bool) -> Value {
+    json!({
+        "error": "txnNotFound",
+        "error_code": 29,
+        "error_message": "Transaction not found.",
+        "searched_all": searched_all,
+        "status": "error"
+    })
+}
+
+#[tokio::test]
+async fn mock_reliable_submit_test() {
+    let (transport, client) = mock_client();
+    let wallet = Wallet::generate(KeyType::Ed25519);
+    let mut payment = Payment::new(
+        wallet.account(),
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap(),
+        Balance::XRP(Drops::from_xrp(25).unwrap()),
+    );
+    payment.common.Fee = Some(Drops::from_drops(12).unwrap());
+    payment.common.Sequence = Some(7);
+    let submitter = ReliableSubmitter::new(client).with_poll_interval(Duration::from_millis(0));
+
+    // Retried after terRETRY, then found in a validated ledger
+    let signed = {
+        let mut payment = serde_json::to_value(&payment).unwrap();
+        payment["LastLedgerSequence"] = json!(120);
+        sign_transaction(&payment, wallet.keypair()).unwrap()
+    };
+    let mut found =
+        serde_json::to_value(TransactionInfo::from_tx_blob(&signed.tx_blob).unwrap()).unwrap();
+    found["meta"] = json!({
+        "AffectedNodes": [],
+        "TransactionIndex": 0,
+        "TransactionResult": "tesSUCCESS"
+    });
+    found["ledger_index"] = json!(102);
+    found["validated"] = json!(true);
+    transport
+        .push_response("ledger", validated_ledger(100))
+        .push_response("submit", submit_response("terRETRY", &signed.tx_blob))
+        .push_response("ledger", validated_ledger(101))
+        .push_response("tx", tx_not_found(false))
+        .push_response("submit", submit_response("tesSUCCESS", &signed.tx_blob))
+        .push_response("ledger", validated_ledger(102))
+        .push_response("tx", found);
+    let outcome = submitter.submit(&payment, wallet.keypair()).await.unwrap();
+    assert_eq!(
+        outcome,
+        SubmitOutcome::Validated {
+            result: EngineResult::TesSuccess,
+            ledger_index: 102,
+            hash: signed.hash.clone(),
+        }
+    );
+    let submitted: Vec<_> = transport
+        .requests()
+        .into_iter()
+        .filter(|(method, _)| method == "submit")
+        .map(|(_, params)| params["tx_blob"].clone())
+        .collect();
+    assert_eq!(
+        submitted,
+        vec![json!(signed.tx_blob), json!(signed.tx_blob)]
+    );
+    assert_eq!(transport.remaining(), 0);
+
+    // Never found up to its LastLedgerSequence
+    let submitter = submitter.with_ledger_offset(2);
+    transport
+        .push_response("ledger", validated_ledger(200))
+        .push_response("submit", submit_response("tesSUCCESS", ""))
+        .push_response("ledger", validated_ledger(201))
+        .push_response("tx", tx_not_found(false))
+        .push_response("ledger", validated_ledger(202))
+        .push_response("tx", tx_not_found(true));
+    let outcome = submitter.submit(&payment, wallet.keypair()).await.unwrap();
+    assert!(matches!(outcome, SubmitOutcome::Expired { .. }));
+    let (method, params) = transport.requests().pop().unwrap();
+    assert_eq!(method, "tx");
+    assert_eq!(
+        (params["min_ledger"].clone(), params["max_ledger"].clone()),
+        (json!(200), json!(202))
+    );
+
+    // Malformed, so it is not looked for
+    transport
+        .push_response("ledger", validated_ledger(300))
+        .push_response("submit", submit_response("temBAD_FEE", ""));
+    let outcome = submitter.submit(&payment, wallet.keypair()).await.unwrap();
+    assert!(matches!(
+        outcome,
+        SubmitOutcome::Rejected {
+            result: EngineResult::TemBadFee,
+            ..
+        }
+    ));
+    assert_eq!(transport.remaining(), 0);
+
+    // Past its LastLedgerSequence, on a node missing some of the ledgers it could be in
+    let submitter = submitter.with_max_polls_past_last_ledger(2);
+    transport
+        .push_response("ledger", validated_ledger(400))
+        .push_response("submit", submit_response("tesSUCCESS", ""))
+        .push_response("ledger", validated_ledger(402))
+        .push_response("tx", tx_not_found(false))
+        .push_response("ledger", validated_ledger(403))
+        .push_response("tx", tx_not_found(false));
+    assert!(submitter.submit(&payment, wallet.keypair()).await.is_err());
+    assert_eq!(transport.remaining(), 0);
+}
+
+#[tokio::test]
+async fn mock_autofill_test() {
+    let (transport, client) = mock_client();
+    let account: Account = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3".parse().unwrap();
+    let payment = Payment::new(
+        account.clone(),
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap(),
+        Balance::XRP(Drops::from_xrp(25).unwrap()),
+    );
+    transport
+        .push_response(
+            "account_info",
+            json!({
+                "account_data": {
+                    "Account": account,
+                    "Balance": "1000000000",
+                    "Flags": 0,
+                    "LedgerEntryType": "AccountRoot",
+                    "OwnerCount": 0,
+                    "PreviousTxnID": "4294BEBE5B569A18C0A2702387C9B1E7146DC3A5850C1E87204951C6FDAA4C42",
+                    "PreviousTxnLgrSeq": 3,
+                    "Sequence": 6,
+                    "index": "92FA6A9FC8EA6018D5D16532D7795C91BFB0831355BDFDA177E86C8BF997985F"
+                },
+                "ledger_current_index": 104,
+                "queue_data": {
+                    "auth_change_queued": false,
+                    "highest_sequence": 7,
+                    "lowest_sequence": 6,
+                    "max_spend_drops_total": "24",
+                    "transactions": [
+                        { "auth_change": false, "fee": "12", "fee_level": "307", "max_spend_drops": "12", "seq": 6 },
+                        { "auth_change": false, "fee": "12", "fee_level": "307", "max_spend_drops": "12", "seq": 7 }
+                    ],
+                    "txn_count": 2
+                },
+                "status": "success",
+                "validated": false
+            }),
+        )
+        .push_response(
+            "fee",
+            json!({
+                "current_ledger_size": "14",
+                "current_queue_size": "2",
+                "drops": {
+                    "base_fee": "10",
+                    "median_fee": "5000",
+                    "minimum_fee": "10",
+                    "open_ledger_fee": "10"
+                },
+                "expected_ledger_size": "24",
+                "ledger_current_index": 104,
+                "levels": {
+                    "median_level": "128000",
+                    "minimum_level": "256",
+                    "open_ledger_level": "256",
+                    "reference_level": "256"
+                },
+                "max_queue_size": "480",
+                "status": "success"
+            }),
+        )
+        .push_response("ledger", validated_ledger(102));
+    let filled = client
+        .autofill(&payment, FeeUrgency::Normal, None, 20)
+        .await
+        .unwrap();
+    assert_eq!(filled.common.Sequence, Some(8));
+    assert_eq!(filled.common.Fee, Some(Drops::from_drops(10).unwrap()));
+    assert_eq!(filled.common.LastLedgerSequence, Some(122));
+    let (method, params) = transport.requests().remove(0);
+    assert_eq!(method, "account_info");
+    assert_eq!(params["queue"], true);
+    assert_eq!(params["ledger_index"], "current");
+
+    // Nothing is asked for what is already set
+    let filled = client
+        .autofill(
+            &filled,
+            FeeUrgency::High,
+            Some(Drops::from_drops(20).unwrap()),
+            20,
+        )
+        .await
+        .unwrap();
+    assert_eq!(filled.common.Sequence, Some(8));
+    assert_eq!(transport.requests().len(), 3);
+
+    let no_account = json!({ "TransactionType": "Payment" });
+    let error = client
+        .autofill(&no_account, FeeUrgency::Normal, None, 20)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, XrpRpcError::InvalidTransaction(_)));
+}
+
+fn account_info_with_sequence(account: &Account, sequence: u32) -> Value {
+    json!({
+        "account_data": {
+            "Account": account,
+            "Balance": "1000000000",
+            "Flags": 0,
+            "LedgerEntryType": "AccountRoot",
+            "OwnerCount": 0,
+            "PreviousTxnID": "4294BEBE5B569A18C0A2702387C9B1E7146DC3A5850C1E87204951C6FDAA4C42",
+            "PreviousTxnLgrSeq": 3,
+            "Sequence": sequence,
+            "index": "92FA6A9FC8EA6018D5D16532D7795C91BFB0831355BDFDA177E86C8BF997985F"
+        },
+        "ledger_current_index": 104,
+        "queue_data": { "txn_count": 0 },
+        "status": "success",
+        "validated": false
+    })
+}
+
+#[tokio::test]
+async fn mock_sequence_manager_test() {
+    let (transport, client) = mock_client();
+    let account: Account = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3".parse().unwrap();
+    let mut account_info = account_info_with_sequence(&account, 6);
+    account_info["queue_data"] =
+        json!({ "highest_sequence": 7, "lowest_sequence": 6, "txn_count": 2 });
+    transport
+        .push_response("account_info", account_info)
+        .push_response("account_info", account_info_with_sequence(&account, 12));
+    let manager = SequenceManager::new(client.clone(), account.clone());
+    let shared = manager.clone();
+    let (first, second, third) = futures::join!(manager.next(), shared.next(), manager.next());
+    let mut sequences = vec![first.unwrap(), second.unwrap(), third.unwrap()];
+    sequences.sort_by_key(|sequence| match sequence {
+        AccountSequence::Sequence(sequence) | AccountSequence::Ticket(sequence) => *sequence,
+    });
+    assert_eq!(
+        sequences,
+        vec![
+            AccountSequence::Sequence(8),
+            AccountSequence::Sequence(9),
+            AccountSequence::Sequence(10)
+        ]
+    );
+    manager
+        .report(AccountSequence::Sequence(8), &EngineResult::TesSuccess)
+        .await;
+    assert_eq!(transport.requests().len(), 1);
+    manager
+        .report(AccountSequence::Sequence(9), &EngineResult::TefPastSeq)
+        .await;
+    assert_eq!(manager.next().await.unwrap(), AccountSequence::Sequence(12));
+    assert_eq!(transport.requests().len(), 2);
+
+    let ticket = |ticket_sequence: u32| {
+        json!({
+            "Account": account,
+            "Flags": 0,
+            "LedgerEntryType": "Ticket",
+            "OwnerNode": "0000000000000000",
+            "PreviousTxnID": "5904C0DC72C58A83AEFED2FFC5386356AA83FCA6A88C89D00646E51E687CDBE4",
+            "PreviousTxnLgrSeq": 16061435,
+            "TicketSequence": ticket_sequence,
+            "index": format!("{:064X}", ticket_sequence)
+        })
+    };
+    transport.push_response(
+        "account_objects",
+        json!({
+            "account": account,
+            "account_objects": [ticket(21), ticket(20)],
+            "ledger_current_index": 104,
+            "validated": false
+        }),
+    );
+    let manager = SequenceManager::new(client, account.clone()).with_tickets();
+    let first = manager.next().await.unwrap();
+    assert_eq!(first, AccountSequence::Ticket(20));
+    manager.report(first, &EngineResult::TemBadFee).await;
+    assert_eq!(manager.next().await.unwrap(), AccountSequence::Ticket(20));
+    let last = manager.next().await.unwrap();
+    assert_eq!(last, AccountSequence::Ticket(21));
+    assert!(manager.next().await.is_err());
+    let (method, params) = transport.requests().pop().unwrap();
+    assert_eq!(method, "account_objects");
+    assert_eq!(params["type"], "ticket");
+
+    let payment = Payment::new(
+        account,
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".parse().unwrap(),
+        Balance::XRP(Drops::from_xrp(25).unwrap()),
+    );
+    let payment = last.fill(&payment).unwrap();
+    assert_eq!(
+        (payment.common.Sequence, payment.common.TicketSequence),
+        (Some(0), Some(21))
+    );
+}
+
+#[tokio::test]
+async fn mock_tx_binary_test() {
+    let (transport, client) = mock_client();
+    let tx_blob = "1200002200000000240000003E6140000002540BE40068400000000000000A7321034AADB09CFF4A4804073701EC53C3510CDC95917C2BB0150FB742D0C66E6CEE9E74473045022022EB32AECEF7C644C891C19F87966DF9C62B1F34BABA6BE774325E4BB8E2DD62022100A51437898C28C2B297112DF8131F2BB39EA5FE613487DDD611525F17962646398114550FC62003E785DC231A1058A05E56E3F09CF4E68314D4CC8AB5B21D86A82C3E9E8D0ECF2404B77FECBA";
+    let meta_blob = hex::encode_upper(
+        to_binary(&json!({
+            "AffectedNodes": [],
+            "TransactionIndex": 2,
+            "TransactionResult": "tesSUCCESS"
+        }))
+        .unwrap(),
+    );
+    let hash = transaction_hash(&hex::decode(tx_blob).unwrap());
+    transport.push_response(
+        "tx",
+        json!({
+            "date": 647619171,
+            "hash": hash,
+            "ledger_index": 54924361,
+            "meta": meta_blob,
+            "status": "success",
+            "tx": tx_blob,
+            "validated": true
+        }),
+    );
+    let tx = match client.tx(&hash, true, None, None).await.unwrap() {
+        TxResult::Found(tx) => tx,
+        other => panic!("Expecting the transaction and got {:?}", other),
+    };
+    assert_eq!(tx.transaction.hash, hash);
+    assert_eq!(tx.transaction.transaction.transaction_type(), "Payment");
+    assert_eq!(tx.transaction.to_tx_blob().unwrap(), tx_blob);
+    assert_eq!(tx.meta.unwrap().TransactionResult, "tesSUCCESS");
+    assert_eq!(tx.ledger_index, Some(54924361));
+    assert_eq!(transport.requests()[0].1["binary"], true);
+}
@@ -0,0 +1,162 @@
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use throttled_xrp_rpc::XRPWebSocketClient;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// A one connection rippled stand-in, answering every command with `success` and then
+/// pushing a ledgerClosed message after a ledger subscription.
+async fn serve_one(mut listener: TcpListener) {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+    while let Some(Ok(Message::Text(text))) = socket.next().await {
+        let request: Value = serde_json::from_str(&text).unwrap();
+        let result = match request["command"].as_str() {
+            Some("subscribe") => json!({}),
+            Some("server_state") => json!({ "state": { "server_state": "full" } }),
+            other => panic!("Unexpected command {:?}", other),
+        };
+        socket
+            .send(Message::Text(
+                json!({
+                    "id": request["id"],
+                    "result": result,
+                    "status": "success",
+                    "type": "response",
+                })
+                .to_string(),
+            ))
+            .await
+            .unwrap();
+        if request["command"] == "subscribe" {
+            socket
+                .send(Message::Text(
+                    json!({
+                        "type": "ledgerClosed",
+                        "fee_base": 10,
+                        "fee_ref": 10,
+                        "ledger_hash": "687F604EF6B2F67319E8DCC8C66EF49D84D18A1E18F948421FC24D2C7C3DB464",
+                        "ledger_index": 7125358,
+                        "ledger_time": 455751310,
+                        "reserve_base": 20000000,
+                        "reserve_inc": 5000000,
+                        "txn_count": 7,
+                        "validated_ledgers": "32570-7125358"
+                    })
+                    .to_string(),
+                ))
+                .await
+                .unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn ws_request_and_subscribe_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve_one(listener));
+
+    let client = XRPWebSocketClient::connect(&url).await.unwrap();
+    let state: Value = client.request("server_state", &json!({})).await.unwrap();
+    assert_eq!(state["state"]["server_state"], "full");
+
+    let mut ledgers = Box::pin(client.subscribe_ledger().await.unwrap());
+    let ledger = ledgers.next().await.unwrap().unwrap();
+    assert_eq!(ledger.ledger_index, 7125358);
+    assert_eq!(ledger.reserve_inc.drops(), 5000000);
+}
+
+const SENDER: &str = "r9HwsqBnAUN4nF6nDqxd4sgP8DrDnDcZP3";
+const ALICE: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+const BOB: &str = "rGWrZyQqhTp9Xu7G5Pkayo7bXjH4k4QYpf";
+
+/// The transaction stream message of a validated XRP payment.
+fn payment_message(destination: &str, hash: &str) -> Message {
+    Message::Text(
+        json!({
+            "type": "transaction",
+            "engine_result": "tesSUCCESS",
+            "engine_result_code": 0,
+            "engine_result_message": "The transaction was applied. Only final in a validated ledger.",
+            "ledger_hash": "687F604EF6B2F67319E8DCC8C66EF49D84D18A1E18F948421FC24D2C7C3DB464",
+            "ledger_index": 7125358,
+            "transaction": {
+                "TransactionType": "Payment",
+                "Account": SENDER,
+                "Amount": "1000000",
+                "Destination": destination,
+                "Fee": "12",
+                "Sequence": 5,
+                "hash": hash,
+            },
+            "validated": true,
+        })
+        .to_string(),
+    )
+}
+
+/// A one connection rippled stand-in for two account subscriptions: once both are made it
+/// pays each account, and after an unsubscribe it pays the second one again.
+async fn serve_accounts(mut listener: TcpListener) {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+    let mut subscriptions = 0;
+    while let Some(Ok(Message::Text(text))) = socket.next().await {
+        let request: Value = serde_json::from_str(&text).unwrap();
+        socket
+            .send(Message::Text(
+                json!({
+                    "id": request["id"],
+                    "result": {},
+                    "status": "success",
+                    "type": "response",
+                })
+                .to_string(),
+            ))
+            .await
+            .unwrap();
+        match request["command"].as_str() {
+            Some("subscribe") => {
+                subscriptions += 1;
+                if subscriptions == 2 {
+                    socket.send(payment_message(ALICE, "A1")).await.unwrap();
+                    socket.send(payment_message(BOB, "B1")).await.unwrap();
+                }
+            }
+            Some("unsubscribe") => {
+                assert_eq!(request["accounts"], json!([ALICE]));
+                socket.send(payment_message(ALICE, "A2")).await.unwrap();
+                socket.send(payment_message(BOB, "B2")).await.unwrap();
+            }
+            other => panic!("Unexpected command {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn ws_account_subscriptions_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve_accounts(listener));
+
+    let client = XRPWebSocketClient::connect(&url).await.unwrap();
+    let alice = ALICE.parse().unwrap();
+    let bob = BOB.parse().unwrap();
+    let mut alice_stream = Box::pin(client.subscribe_accounts(&[alice]).await.unwrap());
+    let mut bob_stream = Box::pin(client.subscribe_accounts(&[bob]).await.unwrap());
+
+    let message = alice_stream.next().await.unwrap().unwrap();
+    assert_eq!(message.transaction.hash, "A1");
+    let message = bob_stream.next().await.unwrap().unwrap();
+    assert_eq!(message.transaction.hash, "B1");
+
+    client
+        .unsubscribe_accounts(&[ALICE.parse().unwrap()])
+        .await
+        .unwrap();
+    let message = bob_stream.next().await.unwrap().unwrap();
+    assert_eq!(message.transaction.hash, "B2");
+    // The Alice stream ended without seeing the payments to Bob, nor the one after it ended
+    assert!(alice_stream.next().await.is_none());
+}